@@ -1,7 +1,7 @@
 // This example is not going to build in this folder.
-// You need to copy this code into your project and add the dependencies whisper_rs and hound in your cargo.toml
+// You need to copy this code into your project and add the dependency whisper_rs,
+// with the `audio` feature enabled, in your cargo.toml
 
-use hound;
 use std::fs::File;
 use std::io::Write;
 use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
@@ -68,41 +68,9 @@ fn main() -> Result<(), &'static str> {
     // Enable token level timestamps
     params.set_token_timestamps(true);
 
-    // Open the audio file.
-    let reader = hound::WavReader::open(wav_path).expect("failed to open file");
-    #[allow(unused_variables)]
-    let hound::WavSpec {
-        channels,
-        sample_rate,
-        bits_per_sample,
-        ..
-    } = reader.spec();
-
-    if sample_rate != 16000 {
-        panic!("sample rate must be 16KHz");
-    }
-
-    // Convert the audio to floating point samples.
-    let samples: Vec<i16> = reader
-        .into_samples::<i16>()
-        .map(|x| x.expect("Invalid sample"))
-        .collect();
-    let mut audio = vec![0.0f32; samples.len().try_into().unwrap()];
-    whisper_rs::convert_integer_to_float_audio(&samples, &mut audio).expect("Conversion error");
-
-    // Convert audio to 16KHz mono f32 samples, as required by the model.
-    // These utilities are provided for convenience, but can be replaced with custom conversion logic.
-    let audio = if channels == 1 {
-        audio
-    } else if channels == 2 {
-        // be sure to initialize the output array to exactly half
-        // the length of the input array
-        let mut output = vec![0.0; audio.len() / 2];
-        whisper_rs::convert_stereo_to_mono_audio(&audio, &mut output).expect("Conversion error");
-        output
-    } else {
-        panic!(">2 channels unsupported");
-    };
+    // Open the audio file, and convert it to 16KHz mono f32 samples, as required by the model.
+    // This utility is provided for convenience, but can be replaced with custom conversion logic.
+    let audio = whisper_rs::load_wav_as_mono_16khz(wav_path).expect("failed to read audio file");
 
     // Run the model.
     state.full(params, &audio[..]).expect("failed to run model");
@@ -116,12 +84,16 @@ fn main() -> Result<(), &'static str> {
         let start_timestamp = segment.start_timestamp();
         let end_timestamp = segment.end_timestamp();
 
-        let first_token_dtw_ts = segment.get_token(0).map_or(-1, |t| t.token_data().t_dtw);
         // Print the segment to stdout.
-        println!(
-            "[{} - {} ({})]: {}",
-            start_timestamp, end_timestamp, first_token_dtw_ts, segment
-        );
+        println!("[{} - {}]: {}", start_timestamp, end_timestamp, segment);
+
+        // With DTW enabled, each word gets its own timestamps.
+        for word in segment.words() {
+            println!(
+                "    [{} - {}]: {}",
+                word.start_centiseconds, word.end_centiseconds, word.text
+            );
+        }
 
         // Format the segment information as a string.
         let line = format!("[{} - {}]: {}\n", start_timestamp, end_timestamp, segment);
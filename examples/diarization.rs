@@ -0,0 +1,50 @@
+/*
+wget https://huggingface.co/akashmjn/tinydiarize-whisper.cpp/resolve/main/ggml-small.en-tdrz.bin
+wget https://github.com/ggerganov/whisper.cpp/raw/master/samples/a13.wav
+cargo run --example diarization --features audio ggml-small.en-tdrz.bin a13.wav
+*/
+
+use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
+
+fn main() {
+    let model_path = std::env::args()
+        .nth(1)
+        .expect("Please specify path to a tinydiarize model as argument 1");
+    let wav_path = std::env::args()
+        .nth(2)
+        .expect("Please specify path to a wav file as argument 2");
+
+    let audio = whisper_rs::load_wav_as_mono_16khz(wav_path).expect("failed to read audio file");
+
+    let ctx = WhisperContext::new_with_params(&model_path, WhisperContextParameters::default())
+        .expect("failed to load model");
+    let mut state = ctx.create_state().expect("failed to create state");
+
+    // speaker turns are only detected by tinydiarize models (those ending in `-tdrz`),
+    // and only if tinydiarize is enabled
+    let params = FullParams::builder(SamplingStrategy::Greedy { best_of: 1 })
+        .language("en")
+        .tdrz_enable(true)
+        .print_progress(false)
+        .build();
+    state.full(params, &audio).expect("failed to run model");
+
+    // each segment says whether the *next* segment is spoken by someone else
+    for segment in state.as_iter() {
+        println!(
+            "[{} - {}]: {}{}",
+            segment.start_timestamp(),
+            segment.end_timestamp(),
+            segment,
+            if segment.next_segment_speaker_turn() {
+                " [SPEAKER_TURN]"
+            } else {
+                ""
+            }
+        );
+    }
+
+    // or, for a two-speaker conversation, label each turn
+    let transcript = state.transcript().expect("failed to read transcript");
+    println!("\n{}", transcript.to_labeled_text());
+}
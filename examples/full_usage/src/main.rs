@@ -1,31 +1,8 @@
 #![allow(clippy::uninlined_format_args)]
 
-use hound::{SampleFormat, WavReader};
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
 use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
 
-fn parse_wav_file(path: PathBuf) -> Vec<i16> {
-    let reader = WavReader::open(path).expect("failed to read file");
-
-    if reader.spec().channels != 1 {
-        panic!("expected mono audio file");
-    }
-    if reader.spec().sample_format != SampleFormat::Int {
-        panic!("expected integer sample format");
-    }
-    if reader.spec().sample_rate != 16000 {
-        panic!("expected 16KHz sample rate");
-    }
-    if reader.spec().bits_per_sample != 16 {
-        panic!("expected 16 bits per sample");
-    }
-
-    reader
-        .into_samples::<i16>()
-        .map(|x| x.expect("sample"))
-        .collect::<Vec<_>>()
-}
-
 fn main() {
     let whisper_path = PathBuf::from(
         std::env::args()
@@ -44,16 +21,11 @@ fn main() {
         panic!("audio file doesn't exist");
     }
 
-    let original_samples = parse_wav_file(audio_path);
-    let mut samples = vec![0.0f32; original_samples.len()];
-    whisper_rs::convert_integer_to_float_audio(&original_samples, &mut samples)
-        .expect("failed to convert samples");
+    let samples =
+        whisper_rs::load_wav_as_mono_16khz(audio_path).expect("failed to read audio file");
 
-    let ctx = WhisperContext::new_with_params(
-        &whisper_path.to_string_lossy(),
-        WhisperContextParameters::default(),
-    )
-    .expect("failed to open model");
+    let ctx = WhisperContext::new_from_path(whisper_path, WhisperContextParameters::default())
+        .expect("failed to open model");
     let mut state = ctx.create_state().expect("failed to create key");
     let mut params = FullParams::new(SamplingStrategy::BeamSearch {
         beam_size: 5,
@@ -1,6 +1,8 @@
 use std::ffi::{c_int, NulError};
 use std::str::Utf8Error;
 
+use crate::{DtwModelPreset, WhisperTokenId};
+
 /// If you have not configured a logging trampoline with [crate::whisper_sys_log::install_whisper_log_trampoline] or
 /// [crate::whisper_sys_tracing::install_whisper_tracing_trampoline],
 /// then `whisper.cpp`'s errors will be output to stderr,
@@ -23,15 +25,18 @@ pub enum WhisperError {
     FailedToEncode,
     /// Failed to run the decoder
     FailedToDecode,
+    /// The language was set to auto-detect, but detecting it failed.
+    FailedToDetectLanguage,
+    /// More decoders were requested (through `best_of` or `beam_size`) than whisper.cpp supports.
+    TooManyDecoders,
+    /// The audio context set with [`crate::FullParams::set_audio_ctx`] is larger than the model's.
+    AudioCtxTooLarge,
     /// Invalid number of mel bands.
     InvalidMelBands,
     /// Invalid thread count
     InvalidThreadCount,
     /// Invalid UTF-8 detected in a string from Whisper.
-    InvalidUtf8 {
-        error_len: Option<usize>,
-        valid_up_to: usize,
-    },
+    InvalidUtf8(Utf8Error),
     /// A null byte was detected in a user-provided string.
     NullByteInString { idx: usize },
     /// Whisper returned a null pointer.
@@ -48,14 +53,45 @@ pub enum WhisperError {
     InputOutputLengthMismatch { input_len: usize, output_len: usize },
     /// Input slice was not an even number of samples.
     HalfSampleMissing(usize),
+    /// The provided sample rate was invalid (zero).
+    InvalidSampleRate(u32),
+    /// Input slice was not a whole number of frames, or the channel count was zero.
+    InvalidChannelLayout { input_len: usize, channels: usize },
+    /// A path could not be passed to whisper.cpp (not valid UTF-8 on a non-Unix platform).
+    InvalidPath,
+    /// A GBNF grammar failed to parse. `pos` is the character offset of the error.
+    InvalidGrammar { reason: &'static str, pos: usize },
+    /// A token ID was outside of the model's vocabulary (`0..n_vocab`).
+    ///
+    /// Returned by the `_checked` token functions, such as [`crate::WhisperContext::token_to_str_checked`],
+    /// where passing the ID to whisper.cpp would abort the process.
+    TokenOutOfBounds {
+        token_id: WhisperTokenId,
+        n_vocab: c_int,
+    },
+    /// An audio file could not be opened, or was not in a supported format.
+    InvalidAudioFile,
+    /// A VAD parameter was out of range, see [`crate::WhisperVadParams::validate`].
+    InvalidVadParams { reason: &'static str },
+    /// A [`crate::WhisperStatePool`] was created with no states.
+    InvalidPoolSize,
+    /// A [`crate::StreamingTranscriber`] window was zero, or not longer than its overlap.
+    InvalidStreamingWindow,
+    /// A callback was set on parameters used to transcribe on several threads at once,
+    /// see [`crate::WhisperContext::full_parallel`].
+    CallbacksNotSupported,
+    /// The DTW model preset doesn't match the loaded model, so its token timestamps would be garbage.
+    /// `n_text_layer` and `n_text_head` are those of the loaded model.
+    DtwPresetMismatch {
+        preset: DtwModelPreset,
+        n_text_layer: c_int,
+        n_text_head: c_int,
+    },
 }
 
 impl From<Utf8Error> for WhisperError {
     fn from(e: Utf8Error) -> Self {
-        Self::InvalidUtf8 {
-            error_len: e.error_len(),
-            valid_up_to: e.valid_up_to(),
-        }
+        Self::InvalidUtf8(e)
     }
 }
 
@@ -81,24 +117,18 @@ impl std::fmt::Display for WhisperError {
             UnableToCalculateEvaluation => write!(f, "Failed to evaluate model."),
             FailedToEncode => write!(f, "Failed to run the encoder."),
             FailedToDecode => write!(f, "Failed to run the decoder."),
-            InvalidMelBands => write!(f, "Invalid number of mel bands."),
-            InvalidThreadCount => write!(f, "Invalid thread count."),
-            InvalidUtf8 {
-                valid_up_to,
-                error_len: Some(len),
-            } => write!(
+            FailedToDetectLanguage => write!(f, "Failed to auto-detect the language."),
+            TooManyDecoders => write!(
                 f,
-                "Invalid UTF-8 detected in a string from Whisper. Index: {}, Length: {}.",
-                valid_up_to, len
+                "Too many decoders requested, lower best_of or beam_size."
             ),
-            InvalidUtf8 {
-                valid_up_to,
-                error_len: None,
-            } => write!(
+            AudioCtxTooLarge => write!(
                 f,
-                "Invalid UTF-8 detected in a string from Whisper. Index: {}.",
-                valid_up_to
+                "The audio context is larger than the model's, lower it with set_audio_ctx."
             ),
+            InvalidMelBands => write!(f, "Invalid number of mel bands."),
+            InvalidThreadCount => write!(f, "Invalid thread count."),
+            InvalidUtf8(e) => write!(f, "Invalid UTF-8 detected in a string from Whisper: {}", e),
             NullByteInString { idx } => write!(
                 f,
                 "A null byte was detected in a user-provided string. Index: {}",
@@ -112,7 +142,8 @@ impl std::fmt::Display for WhisperError {
             FailedToCreateState => write!(f, "Creating a state pointer failed."),
             GenericError(c_int) => write!(
                 f,
-                "Generic whisper error. Varies depending on the function. Error code: {}",
+                "whisper.cpp returned error code {}. Its meaning varies depending on the function; \
+                 whisper.cpp's log output usually has more details.",
                 c_int
             ),
             NoSamples => write!(f, "Input sample buffer was empty."),
@@ -134,8 +165,57 @@ impl std::fmt::Display for WhisperError {
                     size + 1
                 )
             }
+            InvalidSampleRate(rate) => write!(f, "Invalid sample rate: {} Hz", rate),
+            InvalidChannelLayout {
+                input_len,
+                channels,
+            } => write!(
+                f,
+                "Input slice of {} samples can't be split into frames of {} channels",
+                input_len, channels
+            ),
+            InvalidPath => write!(f, "Path can't be passed to whisper.cpp on this platform."),
+            InvalidGrammar { reason, pos } => {
+                write!(f, "Invalid grammar at character {}: {}.", pos, reason)
+            }
+            TokenOutOfBounds { token_id, n_vocab } => write!(
+                f,
+                "Token ID {} is out of bounds for a vocabulary of {} tokens.",
+                token_id, n_vocab
+            ),
+            InvalidAudioFile => write!(f, "Failed to read audio file."),
+            InvalidVadParams { reason } => write!(f, "Invalid VAD parameters: {}.", reason),
+            InvalidPoolSize => write!(f, "A state pool must have at least one state."),
+            InvalidStreamingWindow => write!(
+                f,
+                "Streaming window must be longer than zero and longer than its overlap."
+            ),
+            CallbacksNotSupported => write!(
+                f,
+                "Callbacks can't be used when transcribing on several threads at once."
+            ),
+            DtwPresetMismatch {
+                preset,
+                n_text_layer,
+                n_text_head,
+            } => {
+                let (expected_layers, expected_heads) = preset.text_geometry();
+                write!(
+                    f,
+                    "DTW preset {:?} is for models with {} text layers and {} text heads, \
+                     but the loaded model has {} text layers and {} text heads.",
+                    preset, expected_layers, expected_heads, n_text_layer, n_text_head
+                )
+            }
         }
     }
 }
 
-impl std::error::Error for WhisperError {}
+impl std::error::Error for WhisperError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            WhisperError::InvalidUtf8(e) => Some(e),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,482 @@
+//! Subtitle (SRT and WebVTT), JSON and CSV output for transcription results.
+
+use crate::{WhisperState, Word};
+use std::borrow::Cow;
+use std::fmt::Write;
+
+/// Format every segment of `state` as SRT subtitles.
+///
+/// Segments are numbered from 1, and segments with no text are skipped.
+/// The text of each segment is kept on a single line, see [`to_srt_with_max_width`] for wrapping.
+///
+/// Invalid UTF-8 is replaced with the replacement character.
+pub fn to_srt(state: &WhisperState) -> String {
+    srt(cues(state), None)
+}
+
+/// Format every segment of `state` as SRT subtitles,
+/// wrapping the text of each segment into lines of at most `max_width` characters.
+///
+/// Lines are only broken at whitespace, so a single word longer than `max_width`
+/// is put on a line of its own rather than split.
+///
+/// See [`to_srt`].
+pub fn to_srt_with_max_width(state: &WhisperState, max_width: usize) -> String {
+    srt(cues(state), Some(max_width))
+}
+
+/// Format every segment of `state` as WebVTT subtitles.
+///
+/// Segments with no text are skipped.
+/// Invalid UTF-8 is replaced with the replacement character.
+pub fn to_vtt(state: &WhisperState) -> String {
+    vtt(cues(state).map(|(start, end, text)| (start, end, text, Vec::new())))
+}
+
+/// Format every segment of `state` as WebVTT subtitles,
+/// with the start of each word marked by a timestamp tag and each word wrapped in a `<c>` span.
+///
+/// Words come from [`crate::WhisperSegment::words`], so this is only useful if
+/// [`crate::FullParams::set_token_timestamps`] or DTW is enabled.
+/// Word timestamps that fall outside of their segment, or go backwards, are left out,
+/// as WebVTT requires timestamp tags to be increasing and within their cue.
+///
+/// See [`to_vtt`].
+pub fn to_vtt_with_word_timestamps(state: &WhisperState) -> String {
+    vtt(state.as_iter().filter_map(|segment| {
+        let text = segment.to_str_lossy().ok()?;
+        Some((
+            segment.start_timestamp(),
+            segment.end_timestamp(),
+            text,
+            segment.words(),
+        ))
+    }))
+}
+
+/// Format every segment of `state` as CSV, with a `start_ms,end_ms,text` header row.
+///
+/// Timestamps are in milliseconds. Leading and trailing whitespace is trimmed from the text,
+/// which is quoted if it contains a comma, quote or line break.
+/// Invalid UTF-8 is replaced with the replacement character.
+pub fn to_csv(state: &WhisperState) -> String {
+    csv(cues(state))
+}
+
+/// Format every segment of `state` as JSON, in the same structure as `whisper.cpp`'s `--output-json`.
+///
+/// Only the `result` (the language of the transcription) and `transcription` members are written,
+/// as `systeminfo`, `model` and `params` describe the `whisper.cpp` CLI invocation.
+/// Each entry of `transcription` has `timestamps` (formatted as in SRT), `offsets` (in milliseconds) and `text`.
+///
+/// If `pretty` is true, the output is indented with tabs as `whisper.cpp` does,
+/// otherwise it's written on a single line.
+/// Invalid UTF-8 is replaced with the replacement character.
+pub fn to_json(state: &WhisperState, pretty: bool) -> String {
+    json(state, false, pretty)
+}
+
+/// The same as [`to_json`], but every entry of `transcription` also has a `tokens` array,
+/// as `whisper.cpp`'s `--output-json-full` writes.
+///
+/// Each token has its `text`, `timestamps`, `offsets`, `id`, probability `p` and DTW timestamp `t_dtw`
+/// (-1 if DTW is disabled). Token timestamps are only meaningful if
+/// [`crate::FullParams::set_token_timestamps`] is enabled.
+pub fn to_json_full(state: &WhisperState, pretty: bool) -> String {
+    json(state, true, pretty)
+}
+
+fn json(state: &WhisperState, with_tokens: bool, pretty: bool) -> String {
+    let timing = |start: i64, end: i64| {
+        [
+            (
+                "timestamps",
+                Json::Object(vec![
+                    ("from", Json::String(timestamp(start, ',').into())),
+                    ("to", Json::String(timestamp(end, ',').into())),
+                ]),
+            ),
+            (
+                "offsets",
+                Json::Object(vec![
+                    ("from", Json::Int(start * 10)),
+                    ("to", Json::Int(end * 10)),
+                ]),
+            ),
+        ]
+    };
+
+    let transcription = state
+        .as_iter()
+        .map(|segment| {
+            let mut members = timing(segment.start_timestamp(), segment.end_timestamp()).to_vec();
+            members.push((
+                "text",
+                Json::String(segment.to_str_lossy().unwrap_or_default()),
+            ));
+            if with_tokens {
+                let tokens = segment
+                    .tokens()
+                    .map(|token| {
+                        let data = token.token_data();
+                        // owned, as tokens only live as long as this borrow of `segment`
+                        let text = token.to_str_lossy().unwrap_or_default().into_owned();
+                        let mut members = vec![("text", Json::String(text.into()))];
+                        members.extend(timing(data.t0, data.t1));
+                        members.push(("id", Json::Int(data.id as i64)));
+                        members.push(("p", Json::Float(data.p)));
+                        members.push(("t_dtw", Json::Int(data.t_dtw)));
+                        Json::Object(members)
+                    })
+                    .collect();
+                members.push(("tokens", Json::Array(tokens)));
+            }
+            Json::Object(members)
+        })
+        .collect();
+
+    let mut root = Vec::new();
+    if let Some(language) = crate::get_lang_str(state.full_lang_id_from_state()) {
+        root.push((
+            "result",
+            Json::Object(vec![("language", Json::String(language.into()))]),
+        ));
+    }
+    root.push(("transcription", Json::Array(transcription)));
+
+    let mut out = String::new();
+    Json::Object(root).write(&mut out, pretty.then_some(0));
+    if pretty {
+        out.push('\n');
+    }
+    out
+}
+
+/// Just enough of a JSON value to write `whisper.cpp`'s JSON output without pulling in `serde_json`.
+#[derive(Clone)]
+enum Json<'a> {
+    String(Cow<'a, str>),
+    Int(i64),
+    Float(f32),
+    Array(Vec<Json<'a>>),
+    Object(Vec<(&'static str, Json<'a>)>),
+}
+
+impl Json<'_> {
+    /// Write this value to `out`. If `indent` is `Some`, pretty print it at that nesting depth.
+    fn write(&self, out: &mut String, indent: Option<usize>) {
+        match self {
+            Json::String(s) => write_json_string(out, s),
+            // writing to a String never fails
+            Json::Int(i) => {
+                let _ = write!(out, "{}", i);
+            }
+            // JSON has no representation for NaN or infinity
+            Json::Float(f) if !f.is_finite() => out.push_str("null"),
+            Json::Float(f) => {
+                let _ = write!(out, "{}", f);
+            }
+            Json::Array(items) => {
+                write_json_list(out, indent, '[', ']', items, |out, item, indent| {
+                    item.write(out, indent)
+                })
+            }
+            Json::Object(members) => write_json_list(
+                out,
+                indent,
+                '{',
+                '}',
+                members,
+                |out, (key, value), indent| {
+                    write_json_string(out, key);
+                    out.push_str(if indent.is_some() { ": " } else { ":" });
+                    value.write(out, indent);
+                },
+            ),
+        }
+    }
+}
+
+fn write_json_list<T>(
+    out: &mut String,
+    indent: Option<usize>,
+    open: char,
+    close: char,
+    items: &[T],
+    mut write_item: impl FnMut(&mut String, &T, Option<usize>),
+) {
+    out.push(open);
+    let inner = indent.map(|depth| depth + 1);
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        if let Some(depth) = inner {
+            out.push('\n');
+            out.push_str(&"\t".repeat(depth));
+        }
+        write_item(out, item, inner);
+    }
+    if let (Some(depth), false) = (indent, items.is_empty()) {
+        out.push('\n');
+        out.push_str(&"\t".repeat(depth));
+    }
+    out.push(close);
+}
+
+fn write_json_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c < ' ' => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+fn cues(state: &WhisperState) -> impl Iterator<Item = (i64, i64, Cow<'_, str>)> {
+    state.as_iter().filter_map(|segment| {
+        let text = segment.to_str_lossy().ok()?;
+        Some((segment.start_timestamp(), segment.end_timestamp(), text))
+    })
+}
+
+fn srt<'a>(
+    cues: impl IntoIterator<Item = (i64, i64, Cow<'a, str>)>,
+    max_width: Option<usize>,
+) -> String {
+    let mut out = String::new();
+    let mut index = 0;
+    for (start, end, text) in cues {
+        let text = text.trim();
+        if text.is_empty() {
+            continue;
+        }
+        index += 1;
+        // writing to a String never fails
+        let _ = writeln!(
+            out,
+            "{}\n{} --> {}\n{}\n",
+            index,
+            timestamp(start, ','),
+            timestamp(end, ','),
+            wrap(text, max_width)
+        );
+    }
+    out
+}
+
+fn csv<'a>(cues: impl IntoIterator<Item = (i64, i64, Cow<'a, str>)>) -> String {
+    let mut out = String::from("start_ms,end_ms,text\n");
+    for (start, end, text) in cues {
+        let text = text.trim();
+        // writing to a String never fails
+        let _ = write!(out, "{},{},", start * 10, end * 10);
+        if text.contains([',', '"', '\n', '\r']) {
+            let _ = writeln!(out, "\"{}\"", text.replace('"', "\"\""));
+        } else {
+            let _ = writeln!(out, "{}", text);
+        }
+    }
+    out
+}
+
+fn vtt<'a>(cues: impl IntoIterator<Item = (i64, i64, Cow<'a, str>, Vec<Word>)>) -> String {
+    let mut out = String::from("WEBVTT\n\n");
+    for (start, end, text, words) in cues {
+        let text = text.trim();
+        if text.is_empty() {
+            continue;
+        }
+        // writing to a String never fails
+        let _ = writeln!(out, "{} --> {}", timestamp(start, '.'), timestamp(end, '.'));
+        if words.is_empty() {
+            out.push_str(&escape_vtt(text));
+        } else {
+            let mut last_timestamp = start;
+            for (i, word) in words.iter().enumerate() {
+                if i > 0 {
+                    out.push(' ');
+                    if word.start_centiseconds > last_timestamp && word.start_centiseconds < end {
+                        last_timestamp = word.start_centiseconds;
+                        let _ = write!(out, "<{}>", timestamp(last_timestamp, '.'));
+                    }
+                }
+                let _ = write!(out, "<c>{}</c>", escape_vtt(&word.text));
+            }
+        }
+        out.push_str("\n\n");
+    }
+    out
+}
+
+/// Escape the characters WebVTT cue text treats as markup.
+fn escape_vtt(text: &str) -> Cow<'_, str> {
+    if !text.contains(['&', '<', '>']) {
+        return Cow::Borrowed(text);
+    }
+    Cow::Owned(
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;"),
+    )
+}
+
+/// Format a timestamp in centiseconds as `HH:MM:SS<separator>mmm`.
+fn timestamp(centiseconds: i64, separator: char) -> String {
+    let millis = centiseconds.max(0) * 10;
+    format!(
+        "{:02}:{:02}:{:02}{}{:03}",
+        millis / 3_600_000,
+        millis / 60_000 % 60,
+        millis / 1000 % 60,
+        separator,
+        millis % 1000
+    )
+}
+
+/// Greedily wrap `text` into lines of at most `max_width` characters, breaking at whitespace.
+fn wrap(text: &str, max_width: Option<usize>) -> Cow<'_, str> {
+    let Some(max_width) = max_width else {
+        return Cow::Borrowed(text);
+    };
+    let mut out = String::with_capacity(text.len());
+    let mut line_width = 0;
+    for word in text.split_whitespace() {
+        let width = word.chars().count();
+        if line_width == 0 {
+            line_width = width;
+        } else if line_width + 1 + width <= max_width {
+            out.push(' ');
+            line_width += 1 + width;
+        } else {
+            out.push('\n');
+            line_width = width;
+        }
+        out.push_str(word);
+    }
+    Cow::Owned(out)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_timestamp() {
+        assert_eq!(timestamp(0, ','), "00:00:00,000");
+        assert_eq!(timestamp(123, ','), "00:00:01,230");
+        assert_eq!(timestamp(366_101, ','), "01:01:01,010");
+        assert_eq!(timestamp(-5, ','), "00:00:00,000");
+    }
+
+    #[test]
+    fn test_wrap() {
+        assert_eq!(wrap("one two three", None), "one two three");
+        assert_eq!(wrap("one two three", Some(7)), "one two\nthree");
+        assert_eq!(wrap("one  two", Some(100)), "one two");
+        assert_eq!(
+            wrap("a extraordinarily b", Some(5)),
+            "a\nextraordinarily\nb"
+        );
+    }
+
+    #[test]
+    fn test_srt() {
+        let cues = vec![
+            (0, 150, Cow::Borrowed(" Hello there.")),
+            (150, 200, Cow::Borrowed(" ")),
+            (200, 420, Cow::Borrowed(" General Kenobi!")),
+        ];
+        assert_eq!(
+            srt(cues, Some(10)),
+            "1\n00:00:00,000 --> 00:00:01,500\nHello\nthere.\n\n\
+             2\n00:00:02,000 --> 00:00:04,200\nGeneral\nKenobi!\n\n"
+        );
+    }
+
+    #[test]
+    fn test_csv() {
+        let cues = vec![
+            (0, 150, Cow::Borrowed(" Hello there.")),
+            (150, 420, Cow::Borrowed(" Well, \"General\" Kenobi")),
+        ];
+        assert_eq!(
+            csv(cues),
+            "start_ms,end_ms,text\n\
+             0,1500,Hello there.\n\
+             1500,4200,\"Well, \"\"General\"\" Kenobi\"\n"
+        );
+    }
+
+    #[test]
+    fn test_json() {
+        let value = Json::Object(vec![
+            ("text", Json::String(" \"a\"\\\n\u{1}".into())),
+            (
+                "numbers",
+                Json::Array(vec![Json::Int(-1), Json::Float(0.5)]),
+            ),
+            ("nan", Json::Float(f32::NAN)),
+            ("empty", Json::Array(Vec::new())),
+        ]);
+
+        let mut compact = String::new();
+        value.write(&mut compact, None);
+        assert_eq!(
+            compact,
+            r#"{"text":" \"a\"\\\n\u0001","numbers":[-1,0.5],"nan":null,"empty":[]}"#
+        );
+
+        let mut pretty = String::new();
+        value.write(&mut pretty, Some(0));
+        assert_eq!(
+            pretty,
+            "{\n\t\"text\": \" \\\"a\\\"\\\\\\n\\u0001\",\n\t\"numbers\": [\n\t\t-1,\n\t\t0.5\n\t],\n\t\"nan\": null,\n\t\"empty\": []\n}"
+        );
+    }
+
+    fn word(text: &str, start_centiseconds: i64) -> Word {
+        Word {
+            text: text.to_string(),
+            start_centiseconds,
+            end_centiseconds: 0,
+            confidence: 1.0,
+        }
+    }
+
+    #[test]
+    fn test_vtt() {
+        let cues = vec![
+            (0, 150, Cow::Borrowed(" Hello there."), Vec::new()),
+            (150, 200, Cow::Borrowed(""), Vec::new()),
+            (
+                200,
+                420,
+                Cow::Borrowed(" General Kenobi!"),
+                vec![word("General", 200), word("Kenobi!", 310)],
+            ),
+            (
+                420,
+                500,
+                Cow::Borrowed(" a <b>"),
+                vec![word("a", 420), word("<b>", -1)],
+            ),
+        ];
+        assert_eq!(
+            vtt(cues),
+            "WEBVTT\n\n\
+             00:00:00.000 --> 00:00:01.500\nHello there.\n\n\
+             00:00:02.000 --> 00:00:04.200\n<c>General</c> <00:00:03.100><c>Kenobi!</c>\n\n\
+             00:00:04.200 --> 00:00:05.000\n<c>a</c> <c>&lt;b&gt;</c>\n\n"
+        );
+    }
+}
@@ -21,6 +21,7 @@ unsafe extern "C" fn ggml_logging_trampoline(
 ) {
     if text.is_null() {
         generic_error!("ggml_logging_trampoline: text is nullptr");
+        return;
     }
     let level = GGMLLogLevel::from(level);
 
@@ -28,7 +29,8 @@ unsafe extern "C" fn ggml_logging_trampoline(
     // from_ptr's requirements.
     let log_str = unsafe { CStr::from_ptr(text) }.to_string_lossy();
 
-    ggml_logging_trampoline_safe(level, log_str)
+    // unwinding into C is undefined behavior, so a panicking logger loses this message instead
+    let _ = std::panic::catch_unwind(|| ggml_logging_trampoline_safe(level, log_str));
 }
 
 // this code essentially compiles down to a noop if neither feature is enabled
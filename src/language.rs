@@ -0,0 +1,258 @@
+use std::fmt;
+
+macro_rules! languages {
+    ($($variant:ident => $code:literal, $name:literal;)*) => {
+        /// A language supported by Whisper.
+        ///
+        /// The variants are in the same order as whisper.cpp's language table,
+        /// so a variant's discriminant is its language id.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum Language {
+            $(
+                #[doc = concat!("`", $code, "`: ", $name)]
+                $variant,
+            )*
+        }
+
+        impl Language {
+            /// Every supported language, in order of language id.
+            pub const ALL: &'static [Language] = &[$(Language::$variant),*];
+
+            /// Get the short code of this language, as accepted by [`crate::FullParams::set_language`] (e.g. "de").
+            pub fn as_code(self) -> &'static str {
+                match self {
+                    $(Language::$variant => $code,)*
+                }
+            }
+
+            /// Get the full name of this language, as used by whisper.cpp (e.g. "german").
+            pub fn name(self) -> &'static str {
+                match self {
+                    $(Language::$variant => $name,)*
+                }
+            }
+
+            /// Get the language with the given short code (e.g. "de"). Returns `None` if unsupported.
+            pub fn from_code(code: &str) -> Option<Language> {
+                match code {
+                    $($code => Some(Language::$variant),)*
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+languages! {
+    English => "en", "english";
+    Chinese => "zh", "chinese";
+    German => "de", "german";
+    Spanish => "es", "spanish";
+    Russian => "ru", "russian";
+    Korean => "ko", "korean";
+    French => "fr", "french";
+    Japanese => "ja", "japanese";
+    Portuguese => "pt", "portuguese";
+    Turkish => "tr", "turkish";
+    Polish => "pl", "polish";
+    Catalan => "ca", "catalan";
+    Dutch => "nl", "dutch";
+    Arabic => "ar", "arabic";
+    Swedish => "sv", "swedish";
+    Italian => "it", "italian";
+    Indonesian => "id", "indonesian";
+    Hindi => "hi", "hindi";
+    Finnish => "fi", "finnish";
+    Vietnamese => "vi", "vietnamese";
+    Hebrew => "he", "hebrew";
+    Ukrainian => "uk", "ukrainian";
+    Greek => "el", "greek";
+    Malay => "ms", "malay";
+    Czech => "cs", "czech";
+    Romanian => "ro", "romanian";
+    Danish => "da", "danish";
+    Hungarian => "hu", "hungarian";
+    Tamil => "ta", "tamil";
+    Norwegian => "no", "norwegian";
+    Thai => "th", "thai";
+    Urdu => "ur", "urdu";
+    Croatian => "hr", "croatian";
+    Bulgarian => "bg", "bulgarian";
+    Lithuanian => "lt", "lithuanian";
+    Latin => "la", "latin";
+    Maori => "mi", "maori";
+    Malayalam => "ml", "malayalam";
+    Welsh => "cy", "welsh";
+    Slovak => "sk", "slovak";
+    Telugu => "te", "telugu";
+    Persian => "fa", "persian";
+    Latvian => "lv", "latvian";
+    Bengali => "bn", "bengali";
+    Serbian => "sr", "serbian";
+    Azerbaijani => "az", "azerbaijani";
+    Slovenian => "sl", "slovenian";
+    Kannada => "kn", "kannada";
+    Estonian => "et", "estonian";
+    Macedonian => "mk", "macedonian";
+    Breton => "br", "breton";
+    Basque => "eu", "basque";
+    Icelandic => "is", "icelandic";
+    Armenian => "hy", "armenian";
+    Nepali => "ne", "nepali";
+    Mongolian => "mn", "mongolian";
+    Bosnian => "bs", "bosnian";
+    Kazakh => "kk", "kazakh";
+    Albanian => "sq", "albanian";
+    Swahili => "sw", "swahili";
+    Galician => "gl", "galician";
+    Marathi => "mr", "marathi";
+    Punjabi => "pa", "punjabi";
+    Sinhala => "si", "sinhala";
+    Khmer => "km", "khmer";
+    Shona => "sn", "shona";
+    Yoruba => "yo", "yoruba";
+    Somali => "so", "somali";
+    Afrikaans => "af", "afrikaans";
+    Occitan => "oc", "occitan";
+    Georgian => "ka", "georgian";
+    Belarusian => "be", "belarusian";
+    Tajik => "tg", "tajik";
+    Sindhi => "sd", "sindhi";
+    Gujarati => "gu", "gujarati";
+    Amharic => "am", "amharic";
+    Yiddish => "yi", "yiddish";
+    Lao => "lo", "lao";
+    Uzbek => "uz", "uzbek";
+    Faroese => "fo", "faroese";
+    HaitianCreole => "ht", "haitian creole";
+    Pashto => "ps", "pashto";
+    Turkmen => "tk", "turkmen";
+    Nynorsk => "nn", "nynorsk";
+    Maltese => "mt", "maltese";
+    Sanskrit => "sa", "sanskrit";
+    Luxembourgish => "lb", "luxembourgish";
+    Myanmar => "my", "myanmar";
+    Tibetan => "bo", "tibetan";
+    Tagalog => "tl", "tagalog";
+    Malagasy => "mg", "malagasy";
+    Assamese => "as", "assamese";
+    Tatar => "tt", "tatar";
+    Hawaiian => "haw", "hawaiian";
+    Lingala => "ln", "lingala";
+    Hausa => "ha", "hausa";
+    Bashkir => "ba", "bashkir";
+    Javanese => "jw", "javanese";
+    Sundanese => "su", "sundanese";
+    Cantonese => "yue", "cantonese";
+}
+
+impl Language {
+    /// Get the whisper.cpp language id of this language (e.g. German -> 2).
+    pub fn id(self) -> i32 {
+        self as i32
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// The probability of each language, as returned by [`crate::WhisperState::lang_detect`].
+///
+/// # Examples
+/// ```no_run
+/// # use whisper_rs::LanguageProbabilities;
+/// # let state: whisper_rs::WhisperState = todo!();
+/// let (_, probabilities) = state.lang_detect(0, 1)?;
+/// if let Some((language, probability)) = LanguageProbabilities::from(probabilities).best() {
+///     println!("detected {} with {:.0}% confidence", language, probability * 100.0);
+/// }
+/// # Ok::<(), whisper_rs::WhisperError>(())
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct LanguageProbabilities {
+    probabilities: Vec<f32>,
+}
+
+impl LanguageProbabilities {
+    /// Get the probability of every language, indexed by language id.
+    pub fn as_slice(&self) -> &[f32] {
+        &self.probabilities
+    }
+
+    /// Get the probability of `language`, or 0.0 if there's none for it.
+    pub fn get(&self, language: Language) -> f32 {
+        self.probabilities
+            .get(language.id() as usize)
+            .copied()
+            .unwrap_or(0.0)
+    }
+
+    /// Get the code (e.g. "de") and probability of the most probable language.
+    ///
+    /// Returns `None` if there are no probabilities for any language known to [`Language`].
+    pub fn best(&self) -> Option<(String, f32)> {
+        self.top_n(1).pop()
+    }
+
+    /// Get the codes (e.g. "de") and probabilities of the `n` most probable languages,
+    /// most probable first.
+    ///
+    /// Languages with equal probabilities are ordered by language id.
+    pub fn top_n(&self, n: usize) -> Vec<(String, f32)> {
+        let mut languages = Language::ALL
+            .iter()
+            .zip(&self.probabilities)
+            .collect::<Vec<_>>();
+        // a stable sort, so ties keep language id order
+        languages.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+        languages
+            .into_iter()
+            .take(n)
+            .map(|(language, &probability)| (language.as_code().to_string(), probability))
+            .collect()
+    }
+}
+
+impl From<Vec<f32>> for LanguageProbabilities {
+    /// Wrap probabilities indexed by language id.
+    fn from(probabilities: Vec<f32>) -> Self {
+        Self { probabilities }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_language_codes_round_trip() {
+        assert_eq!(Language::ALL.len(), 100);
+        for (id, &language) in Language::ALL.iter().enumerate() {
+            assert_eq!(language.id(), id as i32);
+            assert_eq!(Language::from_code(language.as_code()), Some(language));
+        }
+        assert_eq!(Language::from_code("xx"), None);
+        assert_eq!(Language::German.to_string(), "german");
+    }
+
+    #[test]
+    fn test_language_probabilities() {
+        let probabilities = LanguageProbabilities::from(vec![0.2, 0.1, 0.6, 0.1]);
+        assert_eq!(probabilities.best(), Some(("de".to_string(), 0.6)));
+        assert_eq!(LanguageProbabilities::from(Vec::new()).best(), None);
+        assert_eq!(
+            probabilities.top_n(3),
+            [
+                ("de".to_string(), 0.6),
+                ("en".to_string(), 0.2),
+                ("zh".to_string(), 0.1)
+            ]
+        );
+        assert_eq!(probabilities.top_n(10).len(), 4);
+        assert_eq!(probabilities.get(Language::Spanish), 0.1);
+        assert_eq!(probabilities.get(Language::Russian), 0.0);
+    }
+}
@@ -9,9 +9,12 @@
 #[cfg(feature = "vulkan")]
 pub mod vulkan;
 
+pub mod format;
+
 mod common_logging;
 mod error;
 mod ggml_logging_hook;
+mod language;
 mod standalone;
 mod utilities;
 mod whisper_ctx;
@@ -20,10 +23,13 @@ mod whisper_grammar;
 mod whisper_logging_hook;
 mod whisper_params;
 mod whisper_state;
+mod whisper_state_pool;
+mod whisper_streaming;
 mod whisper_vad;
 
 pub use common_logging::GGMLLogLevel;
 pub use error::WhisperError;
+pub use language::{Language, LanguageProbabilities};
 pub use standalone::*;
 pub use utilities::*;
 pub use whisper_ctx::DtwMode;
@@ -31,12 +37,19 @@ pub use whisper_ctx::DtwModelPreset;
 pub use whisper_ctx::DtwParameters;
 pub use whisper_ctx::WhisperContextParameters;
 use whisper_ctx::WhisperInnerContext;
-pub use whisper_ctx_wrapper::WhisperContext;
-pub use whisper_grammar::{WhisperGrammarElement, WhisperGrammarElementType};
-pub use whisper_params::{FullParams, SamplingStrategy, SegmentCallbackData};
+pub use whisper_ctx_wrapper::{ModelInfo, WhisperContext};
+pub use whisper_grammar::{WhisperGrammar, WhisperGrammarElement, WhisperGrammarElementType};
+pub use whisper_params::{
+    FullParams, FullParamsBuilder, Progress, SamplingStrategy, SegmentCallbackData,
+};
 #[cfg(feature = "raw-api")]
 pub use whisper_rs_sys;
-pub use whisper_state::{WhisperSegment, WhisperState, WhisperStateSegmentIterator, WhisperToken};
+pub use whisper_state::{
+    Chapter, Transcript, WhisperSegment, WhisperSegmentData, WhisperState,
+    WhisperStateSegmentIterator, WhisperToken, WhisperTokenInfo, Word,
+};
+pub use whisper_state_pool::{PooledState, WhisperStatePool};
+pub use whisper_streaming::{StreamingSegment, StreamingTranscriber};
 pub use whisper_vad::*;
 
 pub type WhisperSysContext = whisper_rs_sys::whisper_context;
@@ -2,21 +2,21 @@
 
 use std::ffi::{c_int, CStr, CString};
 
-/// Return the id of the specified language, returns -1 if not found
+/// Return the id of the specified language (e.g. "de" or "german" -> 2).
+///
+/// Useful to check a language code is supported before passing it to
+/// [`crate::FullParams::set_language`].
 ///
 /// # Arguments
-/// * lang: The language to get the id for.
+/// * lang: The language to get the id for, either a short code or a full name.
 ///
 /// # Returns
-/// The ID of the language, None if not found.
-///
-/// # Panics
-/// Panics if the language contains a null byte.
+/// The ID of the language, None if not found (including if `lang` contains a null byte).
 ///
 /// # C++ equivalent
 /// `int whisper_lang_id(const char * lang)`
 pub fn get_lang_id(lang: &str) -> Option<c_int> {
-    let c_lang = CString::new(lang).expect("Language contains null byte");
+    let c_lang = CString::new(lang).ok()?;
     let ret = unsafe { whisper_rs_sys::whisper_lang_id(c_lang.as_ptr()) };
     if ret == -1 {
         None
@@ -107,23 +107,57 @@ pub fn print_system_info() -> &'static str {
 
 /// Programmatically exposes the information provided by `print_system_info`
 ///
+/// Useful to check whether GPU acceleration or CPU features are actually available,
+/// for example when filing a bug report about performance.
+///
 /// # C++ equivalent
-/// `int ggml_cpu_has_...`
+/// `int ggml_cpu_has_...`, `ggml_backend_reg_name(ggml_backend_reg_get(i))`
+#[derive(Debug, Clone)]
 pub struct SystemInfo {
     pub avx: bool,
     pub avx2: bool,
+    pub avx512: bool,
     pub fma: bool,
     pub f16c: bool,
+    pub neon: bool,
+    /// Whether the CUDA backend was compiled in.
+    pub cuda: bool,
+    /// Whether the Metal backend was compiled in.
+    pub metal: bool,
+    /// Whether the Vulkan backend was compiled in.
+    pub vulkan: bool,
+    /// The names of every GGML backend compiled in (e.g. "CPU", "CUDA").
+    pub backends: Vec<String>,
 }
 
 impl Default for SystemInfo {
     fn default() -> Self {
+        let backends = (0..unsafe { whisper_rs_sys::ggml_backend_reg_count() })
+            .filter_map(|i| {
+                let name = unsafe {
+                    whisper_rs_sys::ggml_backend_reg_name(whisper_rs_sys::ggml_backend_reg_get(i))
+                };
+                (!name.is_null()).then(|| {
+                    unsafe { CStr::from_ptr(name) }
+                        .to_string_lossy()
+                        .into_owned()
+                })
+            })
+            .collect::<Vec<_>>();
+        let has_backend = |name: &str| backends.iter().any(|b| b.eq_ignore_ascii_case(name));
+
         unsafe {
             Self {
                 avx: whisper_rs_sys::ggml_cpu_has_avx() != 0,
                 avx2: whisper_rs_sys::ggml_cpu_has_avx2() != 0,
+                avx512: whisper_rs_sys::ggml_cpu_has_avx512() != 0,
                 fma: whisper_rs_sys::ggml_cpu_has_fma() != 0,
                 f16c: whisper_rs_sys::ggml_cpu_has_f16c() != 0,
+                neon: whisper_rs_sys::ggml_cpu_has_neon() != 0,
+                cuda: has_backend("CUDA"),
+                metal: has_backend("Metal"),
+                vulkan: has_backend("Vulkan"),
+                backends,
             }
         }
     }
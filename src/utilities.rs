@@ -27,9 +27,113 @@ pub fn convert_integer_to_float_audio(
         });
     }
 
+    #[cfg(feature = "simd")]
+    let (samples, output) = simd::integer_to_float(samples, output);
+    integer_to_float_scalar(samples, output);
+
+    Ok(())
+}
+
+/// Lazily convert 16 bit mono audio samples to 32 bit floats, as they're pulled from the iterator.
+///
+/// This is the same conversion as [`convert_integer_to_float_audio`], but without an output buffer,
+/// so samples can be converted as they arrive (e.g. from a capture callback)
+/// and appended straight onto the buffer passed to the model or [`crate::StreamingTranscriber::push_audio`],
+/// without an intermediate allocation.
+///
+/// # Arguments
+/// * `samples` - The 16 bit mono audio samples.
+///
+/// # Examples
+/// ```
+/// # use whisper_rs::convert_integer_to_float_audio_iter;
+/// let mut buffer = Vec::new();
+/// let chunk = [0i16, 16384, -32768];
+/// buffer.extend(convert_integer_to_float_audio_iter(chunk));
+/// assert_eq!(buffer, [0.0, 0.5, -1.0]);
+/// ```
+pub fn convert_integer_to_float_audio_iter<I>(samples: I) -> impl Iterator<Item = f32>
+where
+    I: IntoIterator<Item = i16>,
+{
+    samples.into_iter().map(|sample| sample as f32 / 32768.0)
+}
+
+fn integer_to_float_scalar(samples: &[i16], output: &mut [f32]) {
     for (input, output) in samples.iter().zip(output.iter_mut()) {
         *output = *input as f32 / 32768.0;
     }
+}
+
+/// Convert an array of 32 bit floating point audio samples to 16 bit integer samples.
+///
+/// This is the reverse of [`convert_integer_to_float_audio`].
+/// Samples outside of [-1.0, 1.0] are clamped to the range of `i16` instead of wrapping around.
+///
+/// # Arguments
+/// * `samples` - The array of 32 bit floating point audio samples.
+/// * `output` - The vector of 16 bit integers to write the converted samples to.
+///
+/// # Errors
+/// * if `samples.len() != output.len()` ([`WhisperError::InputOutputLengthMismatch`])
+///
+/// # Examples
+/// ```
+/// # use whisper_rs::convert_float_to_integer_audio;
+/// let samples = [0.0f32; 1024];
+/// let mut output = vec![0i16; samples.len()];
+/// convert_float_to_integer_audio(&samples, &mut output).expect("input and output lengths should be equal");
+/// ```
+pub fn convert_float_to_integer_audio(
+    samples: &[f32],
+    output: &mut [i16],
+) -> Result<(), WhisperError> {
+    if samples.len() != output.len() {
+        return Err(WhisperError::InputOutputLengthMismatch {
+            input_len: samples.len(),
+            output_len: output.len(),
+        });
+    }
+
+    for (input, output) in samples.iter().zip(output.iter_mut()) {
+        // float to int casts saturate, so this clamps to i16::MIN..=i16::MAX
+        *output = (*input * 32768.0) as i16;
+    }
+
+    Ok(())
+}
+
+/// Convert an array of unsigned 8 bit mono audio samples to a vector of 32 bit floats.
+///
+/// 8 bit PCM (such as 8 bit WAV files) is unsigned, centered at 128.
+/// Don't reinterpret it as signed, or it will be transcribed as loud noise.
+///
+/// # Arguments
+/// * `samples` - The array of unsigned 8 bit mono audio samples.
+/// * `output` - The vector of 32 bit floats to write the converted samples to.
+///
+/// # Errors
+/// * if `samples.len() != output.len()` ([`WhisperError::InputOutputLengthMismatch`])
+///
+/// # Examples
+/// ```
+/// # use whisper_rs::convert_u8_to_float_audio;
+/// let samples = [128u8; 1024];
+/// let mut output = vec![1.0f32; samples.len()];
+/// convert_u8_to_float_audio(&samples, &mut output).expect("input and output lengths should be equal");
+/// assert!(output.iter().all(|&s| s == 0.0));
+/// ```
+pub fn convert_u8_to_float_audio(samples: &[u8], output: &mut [f32]) -> Result<(), WhisperError> {
+    if samples.len() != output.len() {
+        return Err(WhisperError::InputOutputLengthMismatch {
+            input_len: samples.len(),
+            output_len: output.len(),
+        });
+    }
+
+    for (input, output) in samples.iter().zip(output.iter_mut()) {
+        *output = (*input as f32 - 128.0) / 128.0;
+    }
 
     Ok(())
 }
@@ -67,13 +171,384 @@ pub fn convert_stereo_to_mono_audio(input: &[f32], output: &mut [f32]) -> Result
         });
     }
 
+    #[cfg(feature = "simd")]
+    let (input, output) = simd::stereo_to_mono(input, output);
+    stereo_to_mono_scalar(input, output);
+
+    Ok(())
+}
+
+fn stereo_to_mono_scalar(input: &[[f32; 2]], output: &mut [f32]) {
     for ([left, right], output) in input.iter().zip(output) {
         *output = (left + right) / 2.0;
     }
+}
+
+// SIMD versions of the conversion loops. Each converts as many samples as it can, 8 at a time,
+// and returns the rest for the scalar version. Results are identical to the scalar versions.
+#[cfg(feature = "simd")]
+mod simd {
+    use wide::{f32x8, i16x8, i32x8};
+
+    const LANES: usize = 8;
+
+    pub(super) fn integer_to_float<'s, 'o>(
+        samples: &'s [i16],
+        output: &'o mut [f32],
+    ) -> (&'s [i16], &'o mut [f32]) {
+        let (samples, samples_rest) = samples.as_chunks::<LANES>();
+        let (output, output_rest) = output.as_chunks_mut::<LANES>();
+        for (input, output) in samples.iter().zip(output) {
+            *output = (i32x8::from(i16x8::new(*input)).round_float() / 32768.0).to_array();
+        }
+        (samples_rest, output_rest)
+    }
+
+    pub(super) fn stereo_to_mono<'s, 'o>(
+        input: &'s [[f32; 2]],
+        output: &'o mut [f32],
+    ) -> (&'s [[f32; 2]], &'o mut [f32]) {
+        let (input, input_rest) = input.as_chunks::<LANES>();
+        let (output, output_rest) = output.as_chunks_mut::<LANES>();
+        for (frames, output) in input.iter().zip(output) {
+            let left = f32x8::new(frames.map(|[left, _]| left));
+            let right = f32x8::new(frames.map(|[_, right]| right));
+            *output = ((left + right) / 2.0).to_array();
+        }
+        (input_rest, output_rest)
+    }
+}
+
+/// Convert 32-bit floating point stereo PCM audio to a newly allocated vector of
+/// 32-bit floating point mono PCM audio.
+///
+/// This is a convenience wrapper around [`convert_stereo_to_mono_audio`].
+/// Use that instead if you want to reuse an output buffer.
+///
+/// # Arguments
+/// * `input` - The array of 32-bit floating point stereo PCM audio samples.
+///
+/// # Errors
+/// * if `input.len()` is odd ([`WhisperError::HalfSampleMissing`])
+///
+/// # Returns
+/// A vector of 32-bit floating point mono PCM audio samples, half the length of `input`.
+///
+/// # Examples
+/// ```
+/// # use whisper_rs::convert_stereo_to_mono_audio_alloc;
+/// let samples = [0.0f32; 1024];
+/// let mono_samples = convert_stereo_to_mono_audio_alloc(&samples).expect("should be no half samples missing");
+/// assert_eq!(mono_samples.len(), 512);
+/// ```
+pub fn convert_stereo_to_mono_audio_alloc(input: &[f32]) -> Result<Vec<f32>, WhisperError> {
+    let mut output = vec![0.0; input.len() / 2];
+    convert_stereo_to_mono_audio(input, &mut output)?;
+    Ok(output)
+}
+
+/// Downmix 32-bit floating point interleaved PCM audio with any number of channels
+/// to 32-bit floating point mono PCM audio, by averaging the channels of each frame.
+///
+/// # Arguments
+/// * `input` - The array of 32-bit floating point interleaved PCM audio samples.
+/// * `channels` - The number of channels in `input`.
+/// * `output` - An output place to write all the mono samples.
+///
+/// # Errors
+/// * if `channels` is 0 or `input.len() % channels != 0` ([`WhisperError::InvalidChannelLayout`])
+/// * if `output.len() != input.len() / channels` ([`WhisperError::InputOutputLengthMismatch`])
+///
+/// # Examples
+/// ```
+/// # use whisper_rs::convert_multichannel_to_mono_audio;
+/// // one second of 5.1 surround sound
+/// let samples = [0.0f32; 6 * 16000];
+/// let mut mono_samples = [0.0f32; 16000];
+/// convert_multichannel_to_mono_audio(&samples, 6, &mut mono_samples).expect("should be whole frames");
+/// ```
+pub fn convert_multichannel_to_mono_audio(
+    input: &[f32],
+    channels: usize,
+    output: &mut [f32],
+) -> Result<(), WhisperError> {
+    if channels == 0 || !input.len().is_multiple_of(channels) {
+        return Err(WhisperError::InvalidChannelLayout {
+            input_len: input.len(),
+            channels,
+        });
+    }
+    let n_frames = input.len() / channels;
+    if output.len() != n_frames {
+        return Err(WhisperError::InputOutputLengthMismatch {
+            input_len: n_frames,
+            output_len: output.len(),
+        });
+    }
+
+    for (frame, output) in input.chunks_exact(channels).zip(output) {
+        *output = frame.iter().sum::<f32>() / channels as f32;
+    }
 
     Ok(())
 }
 
+/// Resample 32-bit floating point mono PCM audio to the 16KHz sample rate required by the model.
+///
+/// This uses linear interpolation, with no low-pass filtering when downsampling.
+/// That's good enough for speech recognition, but not for audio meant to be listened to.
+///
+/// # Arguments
+/// * `input` - The array of 32-bit floating point mono PCM audio samples.
+/// * `input_rate` - The sample rate of `input`, in Hz.
+///
+/// # Errors
+/// * if `input_rate` is 0 ([`WhisperError::InvalidSampleRate`])
+///
+/// # Returns
+/// The resampled audio, with a length of `input.len() * 16000 / input_rate`, rounded to the nearest sample.
+///
+/// # Examples
+/// ```
+/// # use whisper_rs::resample_to_16khz;
+/// let samples = [0.0f32; 48000];
+/// let resampled = resample_to_16khz(&samples, 48000).expect("sample rate should not be zero");
+/// assert_eq!(resampled.len(), 16000);
+/// ```
+pub fn resample_to_16khz(input: &[f32], input_rate: u32) -> Result<Vec<f32>, WhisperError> {
+    const OUTPUT_RATE: u64 = whisper_rs_sys::WHISPER_SAMPLE_RATE as u64;
+
+    if input_rate == 0 {
+        return Err(WhisperError::InvalidSampleRate(input_rate));
+    }
+    if input_rate as u64 == OUTPUT_RATE || input.is_empty() {
+        return Ok(input.to_vec());
+    }
+
+    let output_len =
+        ((input.len() as u64 * OUTPUT_RATE + input_rate as u64 / 2) / input_rate as u64) as usize;
+    let step = input_rate as f64 / OUTPUT_RATE as f64;
+    let last = input.len() - 1;
+    let output = (0..output_len)
+        .map(|i| {
+            let position = i as f64 * step;
+            let idx = (position as usize).min(last);
+            let fraction = (position - idx as f64) as f32;
+            let current = input[idx];
+            let next = input[(idx + 1).min(last)];
+            current + (next - current) * fraction
+        })
+        .collect();
+
+    Ok(output)
+}
+
+/// Read a WAV file into 32-bit floating point mono PCM audio at 16KHz, ready to be passed to the model.
+///
+/// Integer samples of any bit depth and floating point samples are supported.
+/// Multichannel audio is downmixed with [`convert_multichannel_to_mono_audio`],
+/// and other sample rates are resampled with [`resample_to_16khz`].
+///
+/// # Arguments
+/// * `path` - The path of the WAV file.
+///
+/// # Errors
+/// * if the file can't be opened, isn't a WAV file or is truncated ([`WhisperError::InvalidAudioFile`])
+/// * if the file has a sample rate of 0 ([`WhisperError::InvalidSampleRate`])
+///
+/// # Examples
+/// ```no_run
+/// # use whisper_rs::load_wav_as_mono_16khz;
+/// let samples = load_wav_as_mono_16khz("audio.wav").expect("failed to read audio");
+/// ```
+#[cfg(feature = "audio")]
+pub fn load_wav_as_mono_16khz(path: impl AsRef<std::path::Path>) -> Result<Vec<f32>, WhisperError> {
+    let reader = hound::WavReader::open(path).map_err(|_| WhisperError::InvalidAudioFile)?;
+    let spec = reader.spec();
+    let samples = match spec.sample_format {
+        hound::SampleFormat::Float => reader.into_samples::<f32>().collect::<Result<Vec<_>, _>>(),
+        hound::SampleFormat::Int => {
+            let scale = (1i64 << (spec.bits_per_sample - 1)) as f32;
+            reader
+                .into_samples::<i32>()
+                .map(|s| s.map(|s| s as f32 / scale))
+                .collect()
+        }
+    }
+    .map_err(|_| WhisperError::InvalidAudioFile)?;
+
+    let channels = spec.channels as usize;
+    let mono = if channels == 1 {
+        samples
+    } else {
+        let mut mono = vec![0.0; samples.len() / channels.max(1)];
+        convert_multichannel_to_mono_audio(&samples, channels, &mut mono)?;
+        mono
+    };
+    resample_to_16khz(&mono, spec.sample_rate)
+}
+
+/// Compute the fraction of samples whose magnitude is at or above `threshold`.
+///
+/// Clipped audio (samples pinned at ±1.0) transcribes poorly,
+/// so this can be used as a cheap quality check before running the model.
+/// A `threshold` slightly below 1.0, such as 0.999, is usually a good choice.
+///
+/// # Arguments
+/// * `samples` - The array of 32 bit floating point audio samples.
+/// * `threshold` - The magnitude at or above which a sample is considered clipped.
+///
+/// # Returns
+/// A value between 0.0 and 1.0. Returns 0.0 if `samples` is empty.
+///
+/// # Examples
+/// ```
+/// # use whisper_rs::clipping_ratio;
+/// let samples = [0.0f32, 0.5, 1.0, -1.0];
+/// assert_eq!(clipping_ratio(&samples, 0.999), 0.5);
+/// ```
+pub fn clipping_ratio(samples: &[f32], threshold: f32) -> f32 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+
+    let clipped = samples.iter().filter(|s| s.abs() >= threshold).count();
+    clipped as f32 / samples.len() as f32
+}
+
+/// Remove any DC offset from 32 bit floating point audio in place, by subtracting the mean of all samples.
+///
+/// Some microphones record with a constant bias, which shifts the waveform away from zero.
+///
+/// # Arguments
+/// * `samples` - The array of 32 bit floating point audio samples. Empty input is left untouched.
+///
+/// # Examples
+/// ```
+/// # use whisper_rs::remove_dc_offset;
+/// let mut samples = [0.6f32, 0.4, 0.6, 0.4];
+/// remove_dc_offset(&mut samples);
+/// assert!(samples.iter().all(|s| (s.abs() - 0.1).abs() < 1e-6));
+/// ```
+pub fn remove_dc_offset(samples: &mut [f32]) {
+    if samples.is_empty() {
+        return;
+    }
+
+    // sum in f64, as long recordings lose too much precision in f32
+    let mean = (samples.iter().map(|&s| s as f64).sum::<f64>() / samples.len() as f64) as f32;
+    for sample in samples {
+        *sample -= mean;
+    }
+}
+
+/// Scale 32 bit floating point audio in place, so that its loudest sample has a magnitude of `target_peak`.
+///
+/// Quiet recordings can be brought up to a consistent level with this before transcription.
+///
+/// # Arguments
+/// * `samples` - The array of 32 bit floating point audio samples.
+/// * `target_peak` - The magnitude the loudest sample should have afterwards, such as 0.95.
+///
+/// Silent input (where every sample is 0.0) and empty input are left untouched.
+///
+/// # Examples
+/// ```
+/// # use whisper_rs::normalize_peak;
+/// let mut samples = [0.1f32, -0.25, 0.05];
+/// normalize_peak(&mut samples, 1.0);
+/// assert_eq!(samples, [0.4, -1.0, 0.2]);
+/// ```
+pub fn normalize_peak(samples: &mut [f32], target_peak: f32) {
+    let peak = samples.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+    if peak == 0.0 {
+        return;
+    }
+
+    let gain = target_peak / peak;
+    for sample in samples {
+        *sample *= gain;
+    }
+}
+
+/// Remove low frequency rumble (such as mains hum or handling noise) from 32 bit floating point
+/// mono audio in place, with a first order (one-pole) RC high-pass filter.
+///
+/// The filter rolls off at 6 dB per octave below `cutoff_hz`, so it's gentle:
+/// it also removes any DC offset, but won't fully silence frequencies just below the cutoff.
+/// A cutoff of around 80-100 Hz is below the fundamental of most voices.
+///
+/// # Arguments
+/// * `samples` - The array of 32 bit floating point mono audio samples, which is overwritten with the filtered audio.
+/// * `cutoff_hz` - The -3 dB point of the filter, in Hz.
+/// * `sample_rate` - The sample rate of `samples`, in Hz.
+///
+/// If `cutoff_hz` isn't positive, or `sample_rate` is 0, `samples` is left untouched.
+///
+/// # Examples
+/// ```
+/// # use whisper_rs::high_pass_filter;
+/// let mut samples = vec![0.5f32; 16000];
+/// high_pass_filter(&mut samples, 100.0, 16000);
+/// // a constant offset is removed entirely
+/// assert!(samples[15999].abs() < 1e-3);
+/// ```
+pub fn high_pass_filter(samples: &mut [f32], cutoff_hz: f32, sample_rate: u32) {
+    if cutoff_hz.is_nan() || cutoff_hz <= 0.0 || sample_rate == 0 {
+        return;
+    }
+
+    let rc = 1.0 / (2.0 * std::f32::consts::PI * cutoff_hz);
+    let dt = 1.0 / sample_rate as f32;
+    let alpha = rc / (rc + dt);
+
+    let Some((first, rest)) = samples.split_first_mut() else {
+        return;
+    };
+    let mut previous_input = *first;
+    let mut previous_output = *first;
+    for sample in rest {
+        let input = *sample;
+        previous_output = alpha * (previous_output + input - previous_input);
+        previous_input = input;
+        *sample = previous_output;
+    }
+}
+
+/// Run a blocking function on tokio's blocking thread pool, resuming any panic on this task.
+/// The indices of the samples between two timestamps in centiseconds, rounded to the nearest
+/// sample and clamped to `0..n_samples`.
+///
+/// Negative and NaN timestamps become 0, and the range is empty if `end` is before `start`.
+pub(crate) fn centiseconds_to_sample_range(
+    start: f64,
+    end: f64,
+    sample_rate: u32,
+    n_samples: usize,
+) -> std::ops::Range<usize> {
+    let to_sample = |centiseconds: f64| {
+        let sample = (centiseconds * sample_rate as f64 / 100.0).round();
+        // `as` saturates, so negative and NaN timestamps become 0
+        (sample as usize).min(n_samples)
+    };
+    let start = to_sample(start);
+    start..to_sample(end).max(start)
+}
+
+#[cfg(feature = "async")]
+pub(crate) async fn spawn_blocking<T, F>(f: F) -> Result<T, WhisperError>
+where
+    F: FnOnce() -> Result<T, WhisperError> + Send + 'static,
+    T: Send + 'static,
+{
+    match tokio::task::spawn_blocking(f).await {
+        Ok(result) => result,
+        Err(e) if e.is_panic() => std::panic::resume_unwind(e.into_panic()),
+        // only happens if the runtime is shutting down
+        Err(_) => Err(WhisperError::InitError),
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -83,6 +558,44 @@ mod test {
 
     extern crate test;
 
+    #[test]
+    fn test_centiseconds_to_sample_range() {
+        assert_eq!(
+            centiseconds_to_sample_range(0.0, 100.0, 16000, 32000),
+            0..16000
+        );
+        assert_eq!(
+            centiseconds_to_sample_range(10.004, 20.0, 16000, 32000),
+            1601..3200
+        );
+        // rounded rather than truncated
+        assert_eq!(
+            centiseconds_to_sample_range(1.0, 3.0, 11025, 32000),
+            110..331
+        );
+        // clamped to the end of the source
+        assert_eq!(
+            centiseconds_to_sample_range(150.0, 250.0, 16000, 32000),
+            24000..32000
+        );
+        assert_eq!(
+            centiseconds_to_sample_range(300.0, 400.0, 16000, 32000),
+            32000..32000
+        );
+        assert_eq!(
+            centiseconds_to_sample_range(-5.0, 1.0, 16000, 32000),
+            0..160
+        );
+        assert_eq!(
+            centiseconds_to_sample_range(f64::NAN, 1.0, 16000, 32000),
+            0..160
+        );
+        assert_eq!(
+            centiseconds_to_sample_range(50.0, 40.0, 16000, 32000),
+            8000..8000
+        );
+    }
+
     fn random_sample_data<T>() -> Vec<T>
     where
         Standard: Distribution<T>,
@@ -135,6 +648,190 @@ mod test {
         );
     }
 
+    #[test]
+    pub fn assert_integer_to_float_matches_scalar() {
+        // odd length, so the remainder path is exercised too
+        let samples = random_sample_data::<i16>();
+        let samples = &samples[..samples.len() - 3];
+        let mut output = vec![0.0f32; samples.len()];
+        let mut scalar_output = vec![0.0f32; samples.len()];
+        convert_integer_to_float_audio(samples, &mut output).unwrap();
+        integer_to_float_scalar(samples, &mut scalar_output);
+        assert_eq!(output, scalar_output);
+    }
+
+    #[test]
+    pub fn assert_stereo_to_mono_matches_scalar() {
+        let samples = random_sample_data::<f32>();
+        let samples = &samples[..samples.len() - 6];
+        let mut output = vec![0.0f32; samples.len() / 2];
+        let mut scalar_output = vec![0.0f32; samples.len() / 2];
+        convert_stereo_to_mono_audio(samples, &mut output).unwrap();
+        stereo_to_mono_scalar(samples.as_chunks::<2>().0, &mut scalar_output);
+        assert_eq!(output, scalar_output);
+    }
+
+    #[test]
+    pub fn assert_float_to_integer() {
+        let samples = [0.0f32, 0.5, -0.5, 1.0, -1.0, 2.0, -2.0];
+        let mut output = [0i16; 7];
+        convert_float_to_integer_audio(&samples, &mut output).unwrap();
+        assert_eq!(
+            output,
+            [0, 16384, -16384, i16::MAX, i16::MIN, i16::MAX, i16::MIN]
+        );
+        assert!(convert_float_to_integer_audio(&samples, &mut [0i16; 6]).is_err());
+    }
+
+    #[test]
+    pub fn assert_float_to_integer_round_trip() {
+        let samples = random_sample_data::<i16>();
+        let mut floats = vec![0.0; samples.len()];
+        let mut output = vec![0; samples.len()];
+        convert_integer_to_float_audio(&samples, &mut floats).unwrap();
+        convert_float_to_integer_audio(&floats, &mut output).unwrap();
+        assert_eq!(samples, output);
+    }
+
+    #[test]
+    pub fn assert_stereo_to_mono_alloc() {
+        let samples = [1.0f32, 0.0, -1.0, -0.5];
+        assert_eq!(
+            convert_stereo_to_mono_audio_alloc(&samples).unwrap(),
+            vec![0.5, -0.75]
+        );
+        assert!(matches!(
+            convert_stereo_to_mono_audio_alloc(&samples[..3]),
+            Err(WhisperError::HalfSampleMissing(3))
+        ));
+    }
+
+    #[test]
+    pub fn assert_multichannel_to_mono() {
+        let samples = [0.0f32, 0.3, 0.6, -0.3, -0.6, -0.9];
+        let mut output = [0.0f32; 2];
+        convert_multichannel_to_mono_audio(&samples, 3, &mut output).unwrap();
+        assert!((output[0] - 0.3).abs() < 1e-6);
+        assert!((output[1] + 0.6).abs() < 1e-6);
+
+        assert!(matches!(
+            convert_multichannel_to_mono_audio(&samples, 4, &mut output),
+            Err(WhisperError::InvalidChannelLayout {
+                input_len: 6,
+                channels: 4
+            })
+        ));
+        assert!(matches!(
+            convert_multichannel_to_mono_audio(&samples, 0, &mut output),
+            Err(WhisperError::InvalidChannelLayout { .. })
+        ));
+        assert!(matches!(
+            convert_multichannel_to_mono_audio(&samples, 2, &mut output),
+            Err(WhisperError::InputOutputLengthMismatch {
+                input_len: 3,
+                output_len: 2
+            })
+        ));
+    }
+
+    #[test]
+    pub fn assert_resample_to_16khz() {
+        // downsampling keeps every third sample of a 48KHz ramp
+        let samples = (0..48).map(|i| i as f32).collect::<Vec<_>>();
+        let resampled = resample_to_16khz(&samples, 48000).unwrap();
+        assert_eq!(
+            resampled,
+            (0..16).map(|i| (i * 3) as f32).collect::<Vec<_>>()
+        );
+
+        // upsampling interpolates between samples
+        let resampled = resample_to_16khz(&[0.0, 1.0, 2.0], 8000).unwrap();
+        assert_eq!(resampled, vec![0.0, 0.5, 1.0, 1.5, 2.0, 2.0]);
+
+        // 44.1KHz rounds the output length
+        assert_eq!(resample_to_16khz(&[0.0; 441], 44100).unwrap().len(), 160);
+        assert_eq!(resample_to_16khz(&[0.0; 100], 44100).unwrap().len(), 36);
+
+        assert!(matches!(
+            resample_to_16khz(&samples, 0),
+            Err(WhisperError::InvalidSampleRate(0))
+        ));
+    }
+
+    #[test]
+    pub fn assert_u8_to_float() {
+        let samples = [0u8, 64, 128, 255];
+        let mut output = [0.0f32; 4];
+        convert_u8_to_float_audio(&samples, &mut output).unwrap();
+        assert_eq!(output, [-1.0, -0.5, 0.0, 127.0 / 128.0]);
+        assert!(convert_u8_to_float_audio(&samples, &mut [0.0f32; 3]).is_err());
+    }
+
+    #[test]
+    pub fn assert_integer_to_float_iter_matches_slice() {
+        let samples = random_sample_data::<i16>();
+        let mut output = vec![0.0; samples.len()];
+        convert_integer_to_float_audio(&samples, &mut output).unwrap();
+        let lazy = convert_integer_to_float_audio_iter(samples.iter().copied()).collect::<Vec<_>>();
+        assert_eq!(lazy, output);
+    }
+
+    #[test]
+    pub fn assert_clipping_ratio() {
+        assert_eq!(clipping_ratio(&[], 0.999), 0.0);
+        assert_eq!(clipping_ratio(&[0.1, -0.2, 0.3, 0.4], 0.999), 0.0);
+        assert_eq!(clipping_ratio(&[1.0, -1.0, 0.0, 0.5], 0.999), 0.5);
+    }
+
+    #[test]
+    pub fn assert_remove_dc_offset() {
+        let mut samples = [1.5, 0.5, 1.0];
+        remove_dc_offset(&mut samples);
+        assert_eq!(samples, [0.5, -0.5, 0.0]);
+
+        let mut empty: [f32; 0] = [];
+        remove_dc_offset(&mut empty);
+    }
+
+    #[test]
+    pub fn assert_normalize_peak() {
+        let mut samples = [0.25, -0.5, 0.0];
+        normalize_peak(&mut samples, 0.8);
+        assert_eq!(samples, [0.4, -0.8, 0.0]);
+
+        let mut silence = [0.0; 4];
+        normalize_peak(&mut silence, 1.0);
+        assert_eq!(silence, [0.0; 4]);
+        normalize_peak(&mut [], 1.0);
+    }
+
+    #[test]
+    pub fn assert_high_pass_filter() {
+        let sine = |frequency: f32| {
+            (0..16000)
+                .map(|i| (2.0 * std::f32::consts::PI * frequency * i as f32 / 16000.0).sin())
+                .collect::<Vec<_>>()
+        };
+        let peak = |samples: &[f32]| {
+            // skip the first second's transient
+            samples[8000..].iter().fold(0.0f32, |p, s| p.max(s.abs()))
+        };
+
+        let mut low = sine(20.0);
+        high_pass_filter(&mut low, 200.0, 16000);
+        assert!(peak(&low) < 0.15, "{}", peak(&low));
+
+        let mut high = sine(2000.0);
+        high_pass_filter(&mut high, 200.0, 16000);
+        assert!(peak(&high) > 0.95, "{}", peak(&high));
+
+        let mut untouched = [0.5, 0.5];
+        high_pass_filter(&mut untouched, 0.0, 16000);
+        high_pass_filter(&mut untouched, 100.0, 0);
+        assert_eq!(untouched, [0.5, 0.5]);
+        high_pass_filter(&mut [], 100.0, 16000);
+    }
+
     #[bench]
     pub fn bench_stereo_to_mono(b: &mut test::Bencher) {
         let samples = random_sample_data::<f32>();
@@ -158,4 +855,24 @@ mod test {
             ))
         });
     }
+
+    // compare against the benches above with `--features simd`
+    #[bench]
+    pub fn bench_stereo_to_mono_scalar(b: &mut test::Bencher) {
+        let samples = random_sample_data::<f32>();
+        let mut output = vec![0.0; samples.len() / 2];
+        b.iter(|| {
+            stereo_to_mono_scalar(
+                black_box(samples.as_chunks::<2>().0),
+                black_box(&mut output),
+            )
+        });
+    }
+
+    #[bench]
+    pub fn bench_integer_to_float_scalar(b: &mut test::Bencher) {
+        let samples = random_sample_data::<i16>();
+        let mut output = vec![0.0f32; samples.len()];
+        b.iter(|| integer_to_float_scalar(black_box(&samples), black_box(&mut output)));
+    }
 }
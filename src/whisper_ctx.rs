@@ -1,7 +1,9 @@
 use crate::error::WhisperError;
 use crate::WhisperTokenId;
 use std::borrow::Cow;
-use std::ffi::{c_int, CStr, CString};
+use std::ffi::{c_int, c_void, CStr, CString};
+use std::io::Read;
+use std::path::Path;
 
 /// Safe Rust wrapper around a Whisper context.
 ///
@@ -11,6 +13,10 @@ use std::ffi::{c_int, CStr, CString};
 #[derive(Debug)]
 pub struct WhisperInnerContext {
     pub(crate) ctx: *mut whisper_rs_sys::whisper_context,
+    pub(crate) use_gpu: bool,
+    pub(crate) gpu_device: c_int,
+    pub(crate) dtw_token_timestamps: bool,
+    dtw_mode: OwnedDtwMode,
 }
 
 impl WhisperInnerContext {
@@ -30,17 +36,41 @@ impl WhisperInnerContext {
         parameters: WhisperContextParameters,
     ) -> Result<Self, WhisperError> {
         let path_cstr = CString::new(path)?;
+        Self::new_with_cstr(&path_cstr, parameters)
+    }
+
+    /// Create a new WhisperContext from a file, with parameters.
+    ///
+    /// Unlike [`Self::new_with_params`], this accepts paths that aren't valid UTF-8 on Unix.
+    ///
+    /// # Arguments
+    /// * path: The path to the model file.
+    /// * parameters: A parameter struct containing the parameters to use.
+    ///
+    /// # Returns
+    /// Ok(Self) on success, Err(WhisperError) on failure.
+    ///
+    /// # C++ equivalent
+    /// `struct whisper_context * whisper_init_from_file_with_params_no_state(const char * path_model, struct whisper_context_params params);`
+    pub fn new_from_path(
+        path: impl AsRef<Path>,
+        parameters: WhisperContextParameters,
+    ) -> Result<Self, WhisperError> {
+        let path_cstr = path_to_cstring(path.as_ref())?;
+        Self::new_with_cstr(&path_cstr, parameters)
+    }
+
+    fn new_with_cstr(
+        path: &CStr,
+        parameters: WhisperContextParameters,
+    ) -> Result<Self, WhisperError> {
         let ctx = unsafe {
             whisper_rs_sys::whisper_init_from_file_with_params_no_state(
-                path_cstr.as_ptr(),
+                path.as_ptr(),
                 parameters.to_c_struct(),
             )
         };
-        if ctx.is_null() {
-            Err(WhisperError::InitError)
-        } else {
-            Ok(Self { ctx })
-        }
+        Self::from_ptr(ctx, &parameters)
     }
 
     /// Create a new WhisperContext from a buffer.
@@ -64,10 +94,113 @@ impl WhisperInnerContext {
                 parameters.to_c_struct(),
             )
         };
+        Self::from_ptr(ctx, &parameters)
+    }
+
+    /// Create a new WhisperContext, reading the model from `reader` as it loads.
+    ///
+    /// # Arguments
+    /// * reader: The source of the model.
+    ///
+    /// # Returns
+    /// Ok(Self) on success, Err(WhisperError) on failure.
+    ///
+    /// # C++ equivalent
+    /// `struct whisper_context * whisper_init_with_params_no_state(struct whisper_model_loader * loader, struct whisper_context_params params);`
+    pub fn new_from_reader<R: Read>(
+        reader: R,
+        parameters: WhisperContextParameters,
+    ) -> Result<Self, WhisperError> {
+        struct ReaderState<R> {
+            reader: R,
+            // set on end of file, or on any I/O error, after which loading fails
+            eof: bool,
+        }
+
+        unsafe extern "C" fn read<R: Read>(
+            ctx: *mut c_void,
+            output: *mut c_void,
+            read_size: usize,
+        ) -> usize {
+            let state = &mut *(ctx as *mut ReaderState<R>);
+            let output = std::slice::from_raw_parts_mut(output as *mut u8, read_size);
+            // whisper.cpp expects every read to be filled, so don't return short reads
+            let mut filled = 0;
+            while filled < read_size && !state.eof {
+                match state.reader.read(&mut output[filled..]) {
+                    Ok(0) => state.eof = true,
+                    Ok(n) => filled += n,
+                    Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                    Err(_) => state.eof = true,
+                }
+            }
+            filled
+        }
+
+        unsafe extern "C" fn eof<R: Read>(ctx: *mut c_void) -> bool {
+            (*(ctx as *const ReaderState<R>)).eof
+        }
+
+        // the reader is dropped once this function returns, so there's nothing to close
+        unsafe extern "C" fn close(_: *mut c_void) {}
+
+        let mut state = ReaderState { reader, eof: false };
+        let mut loader = whisper_rs_sys::whisper_model_loader {
+            context: &mut state as *mut ReaderState<R> as *mut c_void,
+            read: Some(read::<R>),
+            eof: Some(eof::<R>),
+            close: Some(close),
+        };
+        let ctx = unsafe {
+            whisper_rs_sys::whisper_init_with_params_no_state(&mut loader, parameters.to_c_struct())
+        };
+        Self::from_ptr(ctx, &parameters)
+    }
+
+    /// Take ownership of a context created from `parameters`, checking it was created successfully.
+    fn from_ptr(
+        ctx: *mut whisper_rs_sys::whisper_context,
+        parameters: &WhisperContextParameters,
+    ) -> Result<Self, WhisperError> {
         if ctx.is_null() {
-            Err(WhisperError::InitError)
-        } else {
-            Ok(Self { ctx })
+            return Err(WhisperError::InitError);
+        }
+        let ctx = Self {
+            ctx,
+            use_gpu: parameters.use_gpu,
+            gpu_device: parameters.gpu_device,
+            dtw_token_timestamps: parameters.dtw_token_timestamps(),
+            dtw_mode: OwnedDtwMode::from(&parameters.dtw_parameters.mode),
+        };
+        // a preset for a different model doesn't fail to load, it just produces garbage timestamps
+        if let (true, DtwMode::ModelPreset { model_preset }) =
+            (ctx.dtw_token_timestamps, &parameters.dtw_parameters.mode)
+        {
+            let (n_text_layer, n_text_head) = (ctx.model_n_text_layer(), ctx.model_n_text_head());
+            if model_preset.text_geometry() != (n_text_layer, n_text_head) {
+                // dropping `ctx` frees it
+                return Err(WhisperError::DtwPresetMismatch {
+                    preset: *model_preset,
+                    n_text_layer,
+                    n_text_head,
+                });
+            }
+        }
+        Ok(ctx)
+    }
+
+    /// Get the DTW mode this context was created with.
+    ///
+    /// Note that DTW may still be disabled if flash attention was enabled,
+    /// see [`crate::WhisperState::has_dtw_timestamps`].
+    pub fn dtw_mode(&self) -> DtwMode<'_> {
+        match &self.dtw_mode {
+            OwnedDtwMode::None => DtwMode::None,
+            OwnedDtwMode::TopMost { n_top } => DtwMode::TopMost { n_top: *n_top },
+            OwnedDtwMode::Custom { aheads } => DtwMode::Custom { aheads },
+            OwnedDtwMode::ModelPreset { model_preset } => DtwMode::ModelPreset {
+                model_preset: *model_preset,
+            },
         }
     }
 
@@ -108,6 +241,33 @@ impl WhisperInnerContext {
         }
     }
 
+    /// Convert the provided text into tokens, sizing the buffer with [`Self::token_count`].
+    ///
+    /// # Arguments
+    /// * text: The text to convert.
+    ///
+    /// # Returns
+    /// `Ok(Vec<WhisperTokenId>)` on success, `Err(WhisperError)` on failure.
+    pub fn tokenize_auto(&self, text: &str) -> Result<Vec<WhisperTokenId>, WhisperError> {
+        self.tokenize(text, self.token_count(text)?)
+    }
+
+    /// Count how many tokens the provided text converts into, without converting it.
+    ///
+    /// # Arguments
+    /// * text: The text to count the tokens of.
+    ///
+    /// # Returns
+    /// `Ok(usize)` on success, `Err(WhisperError)` on failure.
+    ///
+    /// # C++ equivalent
+    /// `int whisper_token_count(struct whisper_context * ctx, const char * text);`
+    pub fn token_count(&self, text: &str) -> Result<usize, WhisperError> {
+        let text = CString::new(text)?;
+        let ret = unsafe { whisper_rs_sys::whisper_token_count(self.ctx, text.as_ptr()) };
+        usize::try_from(ret).map_err(|_| WhisperError::InvalidText)
+    }
+
     /// Get n_vocab.
     ///
     /// # Returns
@@ -311,6 +471,13 @@ impl WhisperInnerContext {
     pub fn token_to_bytes(&self, token_id: WhisperTokenId) -> Result<&[u8], WhisperError> {
         Ok(self.token_to_cstr(token_id)?.to_bytes())
     }
+    pub fn token_to_bytes_checked(&self, token_id: WhisperTokenId) -> Result<&[u8], WhisperError> {
+        let n_vocab = self.n_vocab();
+        if !(0..n_vocab).contains(&token_id) {
+            return Err(WhisperError::TokenOutOfBounds { token_id, n_vocab });
+        }
+        self.token_to_bytes(token_id)
+    }
     pub fn token_to_str(&self, token_id: WhisperTokenId) -> Result<&str, WhisperError> {
         Ok(self.token_to_cstr(token_id)?.to_str()?)
     }
@@ -429,19 +596,32 @@ impl Drop for WhisperInnerContext {
     }
 }
 
+pub(crate) fn path_to_cstring(path: &Path) -> Result<CString, WhisperError> {
+    #[cfg(unix)]
+    let bytes = {
+        use std::os::unix::ffi::OsStrExt;
+        path.as_os_str().as_bytes()
+    };
+    // whisper.cpp opens files with `fopen`, which expects a narrow string,
+    // so only UTF-8 paths can be passed through reliably elsewhere
+    #[cfg(not(unix))]
+    let bytes = path.to_str().ok_or(WhisperError::InvalidPath)?.as_bytes();
+    Ok(CString::new(bytes)?)
+}
+
 // following implementations are safe
 // see https://github.com/ggerganov/whisper.cpp/issues/32#issuecomment-1272790388
 unsafe impl Send for WhisperInnerContext {}
 unsafe impl Sync for WhisperInnerContext {}
 
 pub struct WhisperContextParameters<'a> {
-    /// Use GPU if available.
+    /// Use GPU if available. See [`Self::use_gpu`].
     pub use_gpu: bool,
-    /// Enable flash attention, default false
+    /// Enable flash attention, default false. See [`Self::flash_attn`].
     ///
     /// **Warning** Can't be used with DTW. DTW will be disabled if flash_attn is true
     pub flash_attn: bool,
-    /// GPU device id, default 0
+    /// GPU device id, default 0. See [`Self::gpu_device`].
     pub gpu_device: c_int,
     /// DTW token level timestamp parameters
     pub dtw_parameters: DtwParameters<'a>,
@@ -462,14 +642,39 @@ impl<'a> WhisperContextParameters<'a> {
     pub fn new() -> Self {
         Self::default()
     }
+    /// Run the model on a GPU, if whisper.cpp was built with a GPU backend
+    /// (the `cuda`, `hipblas`, `metal`, `vulkan` or `intel-sycl` features).
+    /// Without one, this has no effect and the CPU is used.
+    ///
+    /// Defaults to true if a GPU backend feature is enabled, false otherwise.
     pub fn use_gpu(&mut self, use_gpu: bool) -> &mut Self {
         self.use_gpu = use_gpu;
         self
     }
+    /// Enable flash attention, a fused attention kernel that uses less memory and is faster
+    /// for the encoder, especially on long inputs.
+    ///
+    /// The speedup is largest on GPU backends (CUDA, Metal, Vulkan); on CPU it's usually small,
+    /// and may be a slowdown on older CPUs. Attention is computed in a different order,
+    /// so rounding differs and transcriptions may occasionally change slightly,
+    /// but accuracy isn't meaningfully affected.
+    ///
+    /// **Warning**: flash attention can't be used with DTW token timestamps.
+    /// whisper.cpp silently disables DTW if both are enabled,
+    /// see [`crate::WhisperState::has_dtw_timestamps`].
+    ///
+    /// Defaults to false.
     pub fn flash_attn(&mut self, flash_attn: bool) -> &mut Self {
         self.flash_attn = flash_attn;
         self
     }
+    /// Select which GPU to run the model on, by its index in the backend's device list
+    /// (e.g. the CUDA device ordinal). Only used if [`Self::use_gpu`] is enabled.
+    ///
+    /// This lets servers with several GPUs pin each model to its own device.
+    /// The same setting exists for VAD models, see [`crate::WhisperVadContextParams::set_gpu_device`].
+    ///
+    /// Defaults to 0.
     pub fn gpu_device(&mut self, gpu_device: c_int) -> &mut Self {
         self.gpu_device = gpu_device;
         self
@@ -479,6 +684,11 @@ impl<'a> WhisperContextParameters<'a> {
         self
     }
 
+    // whisper.cpp silently disables DTW when flash attention is enabled
+    fn dtw_token_timestamps(&self) -> bool {
+        !self.flash_attn && !matches!(self.dtw_parameters.mode, DtwMode::None)
+    }
+
     fn to_c_struct(&self) -> whisper_rs_sys::whisper_context_params {
         let dtw_token_timestamps = !matches!(self.dtw_parameters.mode, DtwMode::None);
         let mut dtw_aheads_preset =
@@ -573,7 +783,17 @@ impl<'a> WhisperContextParameters<'a> {
 /// [EXPERIMENTAL] Enable Token-level timestamps with DTW, default Disabled
 #[derive(Debug, Clone)]
 pub struct DtwParameters<'a> {
+    /// Which alignment heads to compute DTW timestamps from.
     pub mode: DtwMode<'a>,
+    /// Size in bytes of the scratch memory whisper.cpp allocates to run DTW on each decoded window.
+    ///
+    /// The memory needed grows with the number of alignment heads and with the number of tokens
+    /// in a window, so large models and dense speech need more. If it's too small,
+    /// ggml runs out of memory and aborts the process when computing timestamps,
+    /// with a "not enough space in the context's memory pool" error.
+    /// Larger values only cost memory while timestamps are being computed.
+    ///
+    /// Defaults to 128 MiB.
     pub dtw_mem_size: usize,
 }
 
@@ -601,11 +821,42 @@ pub enum DtwMode<'a> {
     Custom {
         aheads: &'a [whisper_rs_sys::whisper_ahead],
     },
-    /// Use predefined preset for standard models
+    /// Use predefined preset for standard models.
+    /// Creating a context fails with [`WhisperError::DtwPresetMismatch`] if the preset is for a model of a different size.
     ModelPreset { model_preset: DtwModelPreset },
 }
 
+// copy of a `DtwMode` that doesn't borrow the custom aheads, so the context can keep it
 #[derive(Debug, Clone)]
+enum OwnedDtwMode {
+    None,
+    TopMost {
+        n_top: c_int,
+    },
+    Custom {
+        aheads: Box<[whisper_rs_sys::whisper_ahead]>,
+    },
+    ModelPreset {
+        model_preset: DtwModelPreset,
+    },
+}
+
+impl From<&DtwMode<'_>> for OwnedDtwMode {
+    fn from(mode: &DtwMode<'_>) -> Self {
+        match mode {
+            DtwMode::None => Self::None,
+            DtwMode::TopMost { n_top } => Self::TopMost { n_top: *n_top },
+            DtwMode::Custom { aheads } => Self::Custom {
+                aheads: (*aheads).into(),
+            },
+            DtwMode::ModelPreset { model_preset } => Self::ModelPreset {
+                model_preset: *model_preset,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DtwModelPreset {
     TinyEn,
     Tiny,
@@ -621,6 +872,20 @@ pub enum DtwModelPreset {
     LargeV3Turbo,
 }
 
+impl DtwModelPreset {
+    /// The number of text (decoder) layers and attention heads of the models this preset is for.
+    pub(crate) fn text_geometry(self) -> (c_int, c_int) {
+        match self {
+            Self::TinyEn | Self::Tiny => (4, 6),
+            Self::BaseEn | Self::Base => (6, 8),
+            Self::SmallEn | Self::Small => (12, 12),
+            Self::MediumEn | Self::Medium => (24, 16),
+            Self::LargeV1 | Self::LargeV2 | Self::LargeV3 => (32, 20),
+            Self::LargeV3Turbo => (4, 20),
+        }
+    }
+}
+
 #[cfg(test)]
 #[cfg(feature = "test-with-tiny-model")]
 mod test_with_tiny_model {
@@ -642,4 +907,26 @@ mod test_with_tiny_model {
             .join("");
         assert_eq!(text_in, text_out);
     }
+
+    #[test]
+    fn test_dtw_preset_mismatch() {
+        let mut params = WhisperContextParameters::default();
+        params.dtw_parameters.mode = DtwMode::ModelPreset {
+            model_preset: DtwModelPreset::Base,
+        };
+        assert!(matches!(
+            WhisperInnerContext::new_with_params(MODEL_PATH, params),
+            Err(WhisperError::DtwPresetMismatch {
+                preset: DtwModelPreset::Base,
+                n_text_layer: 4,
+                n_text_head: 6,
+            })
+        ));
+
+        let mut params = WhisperContextParameters::default();
+        params.dtw_parameters.mode = DtwMode::ModelPreset {
+            model_preset: DtwModelPreset::TinyEn,
+        };
+        assert!(WhisperInnerContext::new_with_params(MODEL_PATH, params).is_ok());
+    }
 }
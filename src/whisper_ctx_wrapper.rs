@@ -1,11 +1,55 @@
 use std::borrow::Cow;
 use std::ffi::c_int;
+use std::path::Path;
 use std::sync::Arc;
 
 use crate::{
-    WhisperContextParameters, WhisperError, WhisperInnerContext, WhisperState, WhisperTokenId,
+    DtwMode, FullParams, SystemInfo, Transcript, WhisperContextParameters, WhisperError,
+    WhisperInnerContext, WhisperSegmentData, WhisperState, WhisperTokenId, WhisperVadContext,
+    WhisperVadContextParams,
 };
 
+/// Hyperparameters and type of a loaded model, see [`WhisperContext::model_info`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ModelInfo {
+    /// See [`WhisperContext::model_n_vocab`].
+    pub n_vocab: c_int,
+    /// See [`WhisperContext::model_n_audio_ctx`].
+    pub n_audio_ctx: c_int,
+    /// See [`WhisperContext::model_n_audio_state`].
+    pub n_audio_state: c_int,
+    /// See [`WhisperContext::model_n_audio_head`].
+    pub n_audio_head: c_int,
+    /// See [`WhisperContext::model_n_audio_layer`].
+    pub n_audio_layer: c_int,
+    /// See [`WhisperContext::model_n_text_ctx`].
+    pub n_text_ctx: c_int,
+    /// See [`WhisperContext::model_n_text_state`].
+    pub n_text_state: c_int,
+    /// See [`WhisperContext::model_n_text_head`].
+    pub n_text_head: c_int,
+    /// See [`WhisperContext::model_n_text_layer`].
+    pub n_text_layer: c_int,
+    /// See [`WhisperContext::model_n_mels`].
+    pub n_mels: c_int,
+    /// See [`WhisperContext::model_ftype`].
+    pub ftype: c_int,
+    /// See [`WhisperContext::model_type`].
+    pub model_type: c_int,
+    /// See [`WhisperContext::model_type_readable_str_lossy`]. Empty if whisper.cpp returned a null pointer.
+    pub model_type_readable: String,
+    /// See [`WhisperContext::is_multilingual`].
+    pub is_multilingual: bool,
+}
+
+/// A loaded model.
+///
+/// Cloning a `WhisperContext` is cheap: clones are handles to the same loaded model,
+/// which is freed once the last clone and the last [`WhisperState`] created from it are dropped.
+/// Each clone can create its own states with [`Self::create_state`], so one model can be shared
+/// across threads or async tasks without wrapping it in an `Arc`.
+#[derive(Clone)]
 pub struct WhisperContext {
     ctx: Arc<WhisperInnerContext>,
 }
@@ -34,6 +78,72 @@ impl WhisperContext {
         Ok(Self::wrap(ctx))
     }
 
+    /// Create a new WhisperContext from a file, with parameters.
+    ///
+    /// Unlike [`Self::new_with_params`], this accepts paths that aren't valid UTF-8 on Unix,
+    /// instead of requiring a lossy conversion to `&str`.
+    ///
+    /// # Arguments
+    /// * path: The path to the model file.
+    /// * parameters: A parameter struct containing the parameters to use.
+    ///
+    /// # Returns
+    /// Ok(Self) on success, Err(WhisperError) on failure.
+    /// Fails with [`WhisperError::NullByteInString`] if the path contains a null byte.
+    ///
+    /// # C++ equivalent
+    /// `struct whisper_context * whisper_init_from_file_with_params_no_state(const char * path_model, struct whisper_context_params params);`
+    pub fn new_from_path(
+        path: impl AsRef<Path>,
+        parameters: WhisperContextParameters,
+    ) -> Result<Self, WhisperError> {
+        let ctx = WhisperInnerContext::new_from_path(path, parameters)?;
+        Ok(Self::wrap(ctx))
+    }
+
+    /// Create a new WhisperContext from a file without blocking the async runtime,
+    /// by loading the model on tokio's blocking thread pool.
+    ///
+    /// Requires the `async` feature, and must be called from within a tokio runtime.
+    ///
+    /// # Arguments
+    /// * path: The path to the model file.
+    /// * parameters: A parameter struct containing the parameters to use.
+    ///
+    /// # Returns
+    /// Ok(Self) on success, Err(WhisperError) on failure.
+    #[cfg(feature = "async")]
+    pub async fn new_with_params_async(
+        path: impl AsRef<Path> + Send + 'static,
+        parameters: WhisperContextParameters<'static>,
+    ) -> Result<Self, WhisperError> {
+        crate::utilities::spawn_blocking(move || Self::new_from_path(path, parameters)).await
+    }
+
+    /// Create a new WhisperContext from a file and immediately create `n_states` states for it.
+    ///
+    /// Useful for warming up a server so the first requests don't pay for state initialization.
+    /// If creating any state fails, every state created so far is freed before returning.
+    ///
+    /// # Arguments
+    /// * path: The path to the model file.
+    /// * parameters: A parameter struct containing the parameters to use.
+    /// * n_states: How many states to create.
+    ///
+    /// # Returns
+    /// Ok((Self, Vec<WhisperState>)) on success, Err(WhisperError) on failure.
+    pub fn new_with_states(
+        path: &str,
+        parameters: WhisperContextParameters,
+        n_states: usize,
+    ) -> Result<(Self, Vec<WhisperState>), WhisperError> {
+        let ctx = Self::new_with_params(path, parameters)?;
+        let states = (0..n_states)
+            .map(|_| ctx.create_state())
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok((ctx, states))
+    }
+
     /// Create a new WhisperContext from a buffer.
     ///
     /// # Arguments
@@ -52,6 +162,88 @@ impl WhisperContext {
         Ok(Self::wrap(ctx))
     }
 
+    /// Create a new WhisperContext, reading the model from `reader` as it loads.
+    ///
+    /// Unlike [`Self::new_from_buffer_with_params`], the model never has to be held in memory in full,
+    /// so it can be loaded straight from a network stream or a decompressor.
+    /// The reader doesn't need to be buffered, but reads are small, so unbuffered readers
+    /// (such as [`std::fs::File`] or a socket) will be slow unless wrapped in a [`std::io::BufReader`].
+    ///
+    /// # Arguments
+    /// * reader: The source of the model. Only read up to the end of the model.
+    /// * parameters: A parameter struct containing the parameters to use.
+    ///
+    /// # Returns
+    /// Ok(Self) on success, Err(WhisperError) on failure.
+    /// An I/O error from `reader` is treated as the end of the model, so it fails with [`WhisperError::InitError`].
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use whisper_rs::{WhisperContext, WhisperContextParameters};
+    /// let file = std::fs::File::open("model.bin").expect("failed to open model");
+    /// let ctx = WhisperContext::new_from_reader(
+    ///     std::io::BufReader::new(file),
+    ///     WhisperContextParameters::default(),
+    /// )?;
+    /// # Ok::<(), whisper_rs::WhisperError>(())
+    /// ```
+    ///
+    /// # C++ equivalent
+    /// `struct whisper_context * whisper_init_with_params_no_state(struct whisper_model_loader * loader, struct whisper_context_params params);`
+    pub fn new_from_reader<R: std::io::Read>(
+        reader: R,
+        parameters: WhisperContextParameters,
+    ) -> Result<Self, WhisperError> {
+        let ctx = WhisperInnerContext::new_from_reader(reader, parameters)?;
+        Ok(Self::wrap(ctx))
+    }
+
+    /// Get the DTW mode this context was created with.
+    ///
+    /// Note that DTW may still be disabled if flash attention was enabled,
+    /// see [`WhisperState::has_dtw_timestamps`].
+    pub fn dtw_mode(&self) -> DtwMode<'_> {
+        self.ctx.dtw_mode()
+    }
+
+    /// Create a new WhisperContext from a memory-mapped model file.
+    ///
+    /// This avoids reading the whole model into a heap buffer before handing it to whisper.cpp,
+    /// as [`Self::new_from_buffer_with_params`] would. whisper.cpp copies the weights it needs
+    /// into its own buffers while loading, so the mapping is released before this returns.
+    ///
+    /// Requires the `mmap` feature.
+    ///
+    /// # Safety
+    /// The file must not be modified or truncated while the model is loading,
+    /// by this process or any other. Doing so is undefined behavior.
+    ///
+    /// # Arguments
+    /// * path: The path to the model file.
+    /// * parameters: A parameter struct containing the parameters to use.
+    ///
+    /// # Returns
+    /// Ok(Self) on success, Err(WhisperError) on failure.
+    /// Fails with [`WhisperError::InitError`] if the file can't be opened or mapped.
+    #[cfg(feature = "mmap")]
+    pub unsafe fn new_from_mmap(
+        path: impl AsRef<Path>,
+        parameters: WhisperContextParameters,
+    ) -> Result<Self, WhisperError> {
+        let file = std::fs::File::open(path).map_err(|_| WhisperError::InitError)?;
+        // SAFETY: the caller guarantees the file is not modified while it's mapped
+        let mmap = unsafe { memmap2::Mmap::map(&file) }.map_err(|_| WhisperError::InitError)?;
+        Self::new_from_buffer_with_params(&mmap, parameters)
+    }
+
+    /// Get the CPU features and GPU backends whisper.cpp was built with.
+    ///
+    /// This is the same as [`crate::SystemInfo::default`]. For the raw string whisper.cpp
+    /// prints, see [`crate::print_system_info`].
+    pub fn system_info() -> SystemInfo {
+        SystemInfo::default()
+    }
+
     /// Convert the provided text into tokens.
     ///
     /// # Arguments
@@ -70,6 +262,36 @@ impl WhisperContext {
         self.ctx.tokenize(text, max_tokens)
     }
 
+    /// Convert the provided text into tokens, without a limit on how many tokens it converts into.
+    ///
+    /// Unlike [`Self::tokenize`], this never fails because the text is too long,
+    /// as the number of tokens is counted first with [`Self::token_count`].
+    ///
+    /// # Arguments
+    /// * text: The text to convert.
+    ///
+    /// # Returns
+    /// `Ok(Vec<WhisperTokenId>)` on success, `Err(WhisperError)` on failure.
+    pub fn tokenize_auto(&self, text: &str) -> Result<Vec<WhisperTokenId>, WhisperError> {
+        self.ctx.tokenize_auto(text)
+    }
+
+    /// Count how many tokens the provided text converts into, without converting it.
+    ///
+    /// Use this to size the `max_tokens` argument of [`Self::tokenize`].
+    ///
+    /// # Arguments
+    /// * text: The text to count the tokens of.
+    ///
+    /// # Returns
+    /// `Ok(usize)` on success, `Err(WhisperError)` on failure.
+    ///
+    /// # C++ equivalent
+    /// `int whisper_token_count(struct whisper_context * ctx, const char * text);`
+    pub fn token_count(&self, text: &str) -> Result<usize, WhisperError> {
+        self.ctx.token_count(text)
+    }
+
     /// Get n_vocab.
     ///
     /// # Returns
@@ -283,13 +505,39 @@ impl WhisperContext {
     }
     // --- end model_type_readable ---
 
+    /// Get every `model_n_*` value and the model type at once, such as for logging.
+    ///
+    /// # Returns
+    /// [`ModelInfo`]
+    pub fn model_info(&self) -> ModelInfo {
+        ModelInfo {
+            n_vocab: self.model_n_vocab(),
+            n_audio_ctx: self.model_n_audio_ctx(),
+            n_audio_state: self.model_n_audio_state(),
+            n_audio_head: self.model_n_audio_head(),
+            n_audio_layer: self.model_n_audio_layer(),
+            n_text_ctx: self.model_n_text_ctx(),
+            n_text_state: self.model_n_text_state(),
+            n_text_head: self.model_n_text_head(),
+            n_text_layer: self.model_n_text_layer(),
+            n_mels: self.model_n_mels(),
+            ftype: self.model_ftype(),
+            model_type: self.model_type(),
+            model_type_readable: self
+                .model_type_readable_str_lossy()
+                .map(Cow::into_owned)
+                .unwrap_or_default(),
+            is_multilingual: self.is_multilingual(),
+        }
+    }
+
     // --- begin token functions ---
     /// Convert a token ID to a byte array.
     ///
     /// **Danger**: this function is liable to throw a C++ exception if you pass an out-of-bounds index.
-    /// There is no way to check if your index is in bounds from Rust.
     /// C++ exceptions *cannot* be caught and *will* cause the Rust runtime to abort your program.
-    /// Use this function and its siblings with extreme caution.
+    /// Use this function and its siblings with extreme caution,
+    /// or use [`Self::token_to_bytes_checked`] and [`Self::token_to_str_checked`] instead.
     ///
     /// # Arguments
     /// * `token_id`: ID of the token.
@@ -324,6 +572,59 @@ impl WhisperContext {
         self.ctx.token_to_str(token_id)
     }
 
+    /// Convert a token ID to a byte array, checking that it's in bounds first.
+    ///
+    /// Unlike [`Self::token_to_bytes`], this can't abort your program.
+    ///
+    /// # Arguments
+    /// * `token_id`: ID of the token.
+    ///
+    /// # Returns
+    /// * On success: `Ok(&[u8])`
+    /// * On out-of-bounds index (outside `0..`[`Self::n_vocab`]): `Err(WhisperError::TokenOutOfBounds)`
+    /// * On other error: `Err(WhisperError::NullPointer)`
+    pub fn token_to_bytes_checked(&self, token_id: WhisperTokenId) -> Result<&[u8], WhisperError> {
+        self.ctx.token_to_bytes_checked(token_id)
+    }
+
+    /// Convert a token ID to a string, checking that it's in bounds first.
+    ///
+    /// Unlike [`Self::token_to_str`], this can't abort your program.
+    ///
+    /// # Arguments
+    /// * `token_id`: ID of the token.
+    ///
+    /// # Returns
+    /// * On success: `Ok(&str)`
+    /// * On out-of-bounds index (outside `0..`[`Self::n_vocab`]): `Err(WhisperError::TokenOutOfBounds)`
+    /// * On other error: `Err(WhisperError::NullPointer)` or `Err(WhisperError::InvalidUtf8)`
+    pub fn token_to_str_checked(&self, token_id: WhisperTokenId) -> Result<&str, WhisperError> {
+        Ok(std::str::from_utf8(
+            self.ctx.token_to_bytes_checked(token_id)?,
+        )?)
+    }
+
+    /// Convert a sequence of token IDs back into text.
+    ///
+    /// A single token may hold only part of a multibyte character, so the bytes of every token
+    /// are joined before being validated as UTF-8, rather than each token being converted on its own.
+    /// Every token ID is bounds checked, as in [`Self::token_to_bytes_checked`].
+    ///
+    /// # Arguments
+    /// * `tokens`: IDs of the tokens, in order.
+    ///
+    /// # Returns
+    /// * On success: `Ok(String)`
+    /// * On failure: `Err(WhisperError::TokenOutOfBounds)`, `Err(WhisperError::NullPointer)`
+    ///   or `Err(WhisperError::InvalidUtf8)`
+    pub fn tokens_to_string(&self, tokens: &[WhisperTokenId]) -> Result<String, WhisperError> {
+        let mut bytes = Vec::new();
+        for &token_id in tokens {
+            bytes.extend_from_slice(self.ctx.token_to_bytes_checked(token_id)?);
+        }
+        String::from_utf8(bytes).map_err(|e| e.utf8_error().into())
+    }
+
     /// Convert a token ID to a string.
     ///
     /// This function differs from [`Self::token_to_str`] in that it ignores invalid UTF-8 bytes in the input,
@@ -452,6 +753,119 @@ impl WhisperContext {
 
     // we don't implement `whisper_init()` here since i have zero clue what `whisper_model_loader` does
 
+    /// Create a VAD context that runs on the same device as this context.
+    ///
+    /// The `use_gpu` and `gpu_device` settings of `vad_params` are overridden with the ones
+    /// this context was created with, so VAD and transcription share one GPU instead of
+    /// initializing a second device. The returned context also keeps this model loaded
+    /// for as long as it is alive, even if every [`WhisperContext`] handle is dropped first.
+    ///
+    /// Note whisper.cpp does not currently share backend buffers between the two models,
+    /// so this saves device setup and placement mistakes, not the memory of the VAD model itself.
+    ///
+    /// # Arguments
+    /// * vad_model_path: The path to the VAD model file.
+    /// * vad_params: The VAD context parameters to use.
+    ///
+    /// # Returns
+    /// Ok(WhisperVadContext) on success, Err(WhisperError) on failure.
+    pub fn with_vad(
+        &self,
+        vad_model_path: &str,
+        vad_params: WhisperVadContextParams,
+    ) -> Result<WhisperVadContext, WhisperError> {
+        WhisperVadContext::new_with_parent(vad_model_path, vad_params, self.ctx.clone())
+    }
+
+    /// Split the audio into `n_processors` parts, transcribe each part on its own state
+    /// in its own thread, and merge the results.
+    ///
+    /// This does the same as whisper.cpp's `whisper_full_parallel`, which can't be called directly:
+    /// it transcribes into the context's default state, and whisper-rs creates contexts without one.
+    ///
+    /// Each part is transcribed independently, so words may be lost or garbled at the boundaries
+    /// between parts, and timestamps around them are less accurate.
+    ///
+    /// The offset and duration of `params` are applied once, before the audio is split.
+    /// Callbacks can't be used, as every part would call the same closure at the same time.
+    ///
+    /// # Arguments
+    /// * params: [crate::FullParams] struct, cloned for each part. Must not have any callbacks set.
+    /// * data: raw PCM audio data, 32 bit floating point at a sample rate of 16 kHz, 1 channel.
+    /// * n_processors: How many parts to split the audio into. Must be at least 1.
+    ///
+    /// # Returns
+    /// Ok(Transcript) on success, with segment and token timestamps relative to the start of `data`.
+    /// Err(WhisperError) on failure, or [`WhisperError::CallbacksNotSupported`] if `params` has callbacks.
+    ///
+    /// # Panics
+    /// If transcribing any of the parts panics, the panic is resumed on the calling thread.
+    ///
+    /// # C++ equivalent
+    /// `int whisper_full_parallel(struct whisper_context * ctx, struct whisper_full_params params, const float * samples, int n_samples, int n_processors)`
+    pub fn full_parallel(
+        &self,
+        mut params: FullParams,
+        data: &[f32],
+        n_processors: usize,
+    ) -> Result<Transcript, WhisperError> {
+        if n_processors < 1 {
+            return Err(WhisperError::InvalidThreadCount);
+        }
+        if params.has_callbacks() {
+            return Err(WhisperError::CallbacksNotSupported);
+        }
+
+        // apply the offset and duration here, rather than again within every part
+        let range = WhisperState::processed_sample_range(&params, data.len());
+        let range_start = range.start;
+        let data = &data[range];
+        params.set_offset_ms(0);
+        params.set_duration_ms(0);
+        if data.is_empty() {
+            return Err(WhisperError::NoSamples);
+        }
+
+        let chunk_len = data.len().div_ceil(n_processors);
+        let states = (0..data.len().div_ceil(chunk_len))
+            .map(|_| self.create_state())
+            .collect::<Result<Vec<_>, _>>()?;
+        let results = std::thread::scope(|scope| {
+            let handles = states
+                .into_iter()
+                .zip(data.chunks(chunk_len))
+                .map(|(mut state, chunk)| {
+                    let params = params.clone();
+                    scope.spawn(move || {
+                        state.full(params, chunk)?;
+                        state.transcript()
+                    })
+                })
+                .collect::<Vec<_>>();
+            handles
+                .into_iter()
+                .map(|handle| {
+                    handle
+                        .join()
+                        .unwrap_or_else(|payload| std::panic::resume_unwind(payload))
+                })
+                .collect::<Result<Vec<_>, _>>()
+        })?;
+
+        let mut segments: Vec<WhisperSegmentData> = Vec::new();
+        for (chunk_idx, transcript) in results.into_iter().enumerate() {
+            // offset of this chunk from the start of `data`, in centiseconds
+            let chunk_start = range_start + chunk_idx * chunk_len;
+            let offset = (chunk_start * 100 / whisper_rs_sys::WHISPER_SAMPLE_RATE as usize) as i64;
+            for mut segment in transcript.segments {
+                segment.segment = segments.len() as c_int;
+                segment.shift_timestamps(offset);
+                segments.push(segment);
+            }
+        }
+        Ok(Transcript::new(segments))
+    }
+
     /// Create a new state object, ready for use.
     ///
     /// # Returns
@@ -1,3 +1,5 @@
+use crate::WhisperError;
+use std::collections::HashMap;
 use whisper_rs_sys::{
     whisper_gretype_WHISPER_GRETYPE_ALT, whisper_gretype_WHISPER_GRETYPE_CHAR,
     whisper_gretype_WHISPER_GRETYPE_CHAR_ALT, whisper_gretype_WHISPER_GRETYPE_CHAR_NOT,
@@ -78,3 +80,433 @@ impl WhisperGrammarElement {
         }
     }
 }
+
+/// A grammar parsed from [GBNF](https://github.com/ggml-org/llama.cpp/blob/master/grammars/README.md),
+/// used to constrain decoding with [`crate::FullParams::set_gbnf_grammar`].
+///
+/// Supported syntax: rules (`name ::= ...`), string literals, character classes (`[a-z]`, `[^"]`),
+/// grouping, alternation (`|`), repetition (`*`, `+`, `?`) and `#` comments.
+///
+/// # Examples
+/// ```
+/// # use whisper_rs::WhisperGrammar;
+/// let grammar = WhisperGrammar::parse(r#"
+///     root   ::= "turn " ("on" | "off") " the " device
+///     device ::= "lights" | "fan"
+/// "#).unwrap();
+/// assert_eq!(grammar.rule_index("root"), Some(0));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WhisperGrammar {
+    rules: Vec<Vec<WhisperGrammarElement>>,
+    symbol_ids: HashMap<String, u32>,
+}
+
+impl WhisperGrammar {
+    /// Parse a GBNF grammar.
+    ///
+    /// # Returns
+    /// Ok(Self) on success, Err([`WhisperError::InvalidGrammar`]) if the grammar
+    /// has a syntax error or references an undefined rule.
+    pub fn parse(src: &str) -> Result<Self, WhisperError> {
+        let mut parser = GrammarParser {
+            src: src.chars().collect(),
+            rules: Vec::new(),
+            symbol_ids: HashMap::new(),
+        };
+        let mut pos = parser.parse_space(0, true);
+        while pos < parser.src.len() {
+            pos = parser.parse_rule(pos)?;
+        }
+
+        for rule in &parser.rules {
+            for element in rule {
+                if element.element_type == WhisperGrammarElementType::RuleReference
+                    && parser
+                        .rules
+                        .get(element.value as usize)
+                        .is_none_or(|r| r.is_empty())
+                {
+                    return Err(WhisperError::InvalidGrammar {
+                        reason: "undefined rule",
+                        pos: src.chars().count(),
+                    });
+                }
+            }
+        }
+
+        Ok(Self {
+            rules: parser.rules,
+            symbol_ids: parser.symbol_ids,
+        })
+    }
+
+    /// Get the index of the rule called `name`, to pass as the start rule.
+    pub fn rule_index(&self, name: &str) -> Option<usize> {
+        self.symbol_ids.get(name).map(|&id| id as usize)
+    }
+
+    /// Get the rules of this grammar. Each rule ends with a [`WhisperGrammarElementType::End`] element.
+    pub fn rules(&self) -> &[Vec<WhisperGrammarElement>] {
+        &self.rules
+    }
+}
+
+/// Grammar rules in the layout `whisper_full_params.grammar_rules` expects:
+/// an array of pointers to rules, each an array of elements ending with an END element.
+pub(crate) struct GrammarRules {
+    // the pointers point into these, which never move once built
+    _rules: Vec<Vec<whisper_rs_sys::whisper_grammar_element>>,
+    pointers: Vec<*const whisper_rs_sys::whisper_grammar_element>,
+}
+
+// the pointers only point into `_rules`, and neither is ever modified after construction
+unsafe impl Send for GrammarRules {}
+unsafe impl Sync for GrammarRules {}
+
+impl GrammarRules {
+    pub(crate) fn new<'a>(rules: impl IntoIterator<Item = &'a [WhisperGrammarElement]>) -> Self {
+        let rules = rules
+            .into_iter()
+            .map(|rule| {
+                let mut rule = rule.iter().map(|e| e.to_c_type()).collect::<Vec<_>>();
+                if rule
+                    .last()
+                    .is_none_or(|e| e.type_ != WhisperGrammarElementType::End.into())
+                {
+                    rule.push(
+                        WhisperGrammarElement::new(WhisperGrammarElementType::End, 0).to_c_type(),
+                    );
+                }
+                rule
+            })
+            .collect::<Vec<_>>();
+        let pointers = rules.iter().map(|rule| rule.as_ptr()).collect();
+        Self {
+            _rules: rules,
+            pointers,
+        }
+    }
+
+    pub(crate) fn as_ptr(&self) -> *mut *const whisper_rs_sys::whisper_grammar_element {
+        // whisper.cpp never writes through this pointer
+        self.pointers.as_ptr() as *mut _
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.pointers.len()
+    }
+}
+
+struct GrammarParser {
+    src: Vec<char>,
+    rules: Vec<Vec<WhisperGrammarElement>>,
+    symbol_ids: HashMap<String, u32>,
+}
+
+impl GrammarParser {
+    fn at(&self, pos: usize) -> Option<char> {
+        self.src.get(pos).copied()
+    }
+
+    fn error<T>(reason: &'static str, pos: usize) -> Result<T, WhisperError> {
+        Err(WhisperError::InvalidGrammar { reason, pos })
+    }
+
+    fn get_symbol_id(&mut self, name: &str) -> u32 {
+        let next_id = self.symbol_ids.len() as u32;
+        *self.symbol_ids.entry(name.to_string()).or_insert(next_id)
+    }
+
+    fn generate_symbol_id(&mut self, base_name: &str) -> u32 {
+        let next_id = self.symbol_ids.len() as u32;
+        self.symbol_ids
+            .insert(format!("{}_{}", base_name, next_id), next_id);
+        next_id
+    }
+
+    fn add_rule(&mut self, rule_id: u32, rule: Vec<WhisperGrammarElement>) {
+        let rule_id = rule_id as usize;
+        if self.rules.len() <= rule_id {
+            self.rules.resize(rule_id + 1, Vec::new());
+        }
+        self.rules[rule_id] = rule;
+    }
+
+    fn parse_space(&self, mut pos: usize, newline_ok: bool) -> usize {
+        while let Some(c) = self.at(pos) {
+            match c {
+                ' ' | '\t' => pos += 1,
+                '\r' | '\n' if newline_ok => pos += 1,
+                '#' => {
+                    while !matches!(self.at(pos), None | Some('\r' | '\n')) {
+                        pos += 1;
+                    }
+                }
+                _ => break,
+            }
+        }
+        pos
+    }
+
+    fn parse_name(&self, pos: usize) -> Result<(String, usize), WhisperError> {
+        let mut end = pos;
+        while self
+            .at(end)
+            .is_some_and(|c| c.is_ascii_alphanumeric() || c == '-')
+        {
+            end += 1;
+        }
+        if end == pos {
+            return Self::error("expecting name", pos);
+        }
+        Ok((self.src[pos..end].iter().collect(), end))
+    }
+
+    fn parse_char(&self, pos: usize) -> Result<(u32, usize), WhisperError> {
+        match self.at(pos) {
+            None => Self::error("unexpected end of input", pos),
+            Some('\\') => {
+                let hex_digits = match self.at(pos + 1) {
+                    Some('x') => 2,
+                    Some('u') => 4,
+                    Some('U') => 8,
+                    Some('t') => return Ok(('\t' as u32, pos + 2)),
+                    Some('r') => return Ok(('\r' as u32, pos + 2)),
+                    Some('n') => return Ok(('\n' as u32, pos + 2)),
+                    Some(c @ ('\\' | '"' | '[' | ']')) => return Ok((c as u32, pos + 2)),
+                    _ => return Self::error("unknown escape", pos),
+                };
+                let start = pos + 2;
+                let mut value = 0;
+                for i in start..start + hex_digits {
+                    let Some(digit) = self.at(i).and_then(|c| c.to_digit(16)) else {
+                        return Self::error("expecting hex digit", i);
+                    };
+                    value = value << 4 | digit;
+                }
+                Ok((value, start + hex_digits))
+            }
+            Some(c) => Ok((c as u32, pos + 1)),
+        }
+    }
+
+    fn parse_sequence(
+        &mut self,
+        mut pos: usize,
+        rule_name: &str,
+        out: &mut Vec<WhisperGrammarElement>,
+        is_nested: bool,
+    ) -> Result<usize, WhisperError> {
+        use WhisperGrammarElementType::*;
+
+        let mut last_sym_start = out.len();
+        while let Some(c) = self.at(pos) {
+            match c {
+                '"' => {
+                    pos += 1;
+                    last_sym_start = out.len();
+                    while self.at(pos) != Some('"') {
+                        let (value, next) = self.parse_char(pos)?;
+                        out.push(WhisperGrammarElement::new(Character, value));
+                        pos = next;
+                    }
+                    pos = self.parse_space(pos + 1, is_nested);
+                }
+                '[' => {
+                    pos += 1;
+                    let mut start_type = Character;
+                    if self.at(pos) == Some('^') {
+                        pos += 1;
+                        start_type = NotCharacter;
+                    }
+                    last_sym_start = out.len();
+                    while self.at(pos) != Some(']') {
+                        let (value, next) = self.parse_char(pos)?;
+                        pos = next;
+                        let element_type = if last_sym_start < out.len() {
+                            CharacterAlternate
+                        } else {
+                            start_type
+                        };
+                        out.push(WhisperGrammarElement::new(element_type, value));
+                        if self.at(pos) == Some('-')
+                            && !matches!(self.at(pos + 1), None | Some(']'))
+                        {
+                            let (upper, next) = self.parse_char(pos + 1)?;
+                            pos = next;
+                            out.push(WhisperGrammarElement::new(CharacterRangeUpper, upper));
+                        }
+                    }
+                    pos = self.parse_space(pos + 1, is_nested);
+                }
+                c if c.is_ascii_alphanumeric() || c == '-' => {
+                    let (name, name_end) = self.parse_name(pos)?;
+                    let ref_id = self.get_symbol_id(&name);
+                    pos = self.parse_space(name_end, is_nested);
+                    last_sym_start = out.len();
+                    out.push(WhisperGrammarElement::new(RuleReference, ref_id));
+                }
+                '(' => {
+                    pos = self.parse_space(pos + 1, true);
+                    let sub_rule_id = self.generate_symbol_id(rule_name);
+                    pos = self.parse_alternates(pos, rule_name, sub_rule_id, true)?;
+                    last_sym_start = out.len();
+                    out.push(WhisperGrammarElement::new(RuleReference, sub_rule_id));
+                    if self.at(pos) != Some(')') {
+                        return Self::error("expecting ')'", pos);
+                    }
+                    pos = self.parse_space(pos + 1, is_nested);
+                }
+                '*' | '+' | '?' => {
+                    if last_sym_start == out.len() {
+                        return Self::error("expecting preceding item to */+/?", pos);
+                    }
+                    // S* --> S' ::= S S' |
+                    // S+ --> S' ::= S S' | S
+                    // S? --> S' ::= S |
+                    let sub_rule_id = self.generate_symbol_id(rule_name);
+                    let mut sub_rule = out[last_sym_start..].to_vec();
+                    if c != '?' {
+                        sub_rule.push(WhisperGrammarElement::new(RuleReference, sub_rule_id));
+                    }
+                    sub_rule.push(WhisperGrammarElement::new(Alternate, 0));
+                    if c == '+' {
+                        sub_rule.extend_from_slice(&out[last_sym_start..]);
+                    }
+                    sub_rule.push(WhisperGrammarElement::new(End, 0));
+                    self.add_rule(sub_rule_id, sub_rule);
+
+                    out.truncate(last_sym_start);
+                    out.push(WhisperGrammarElement::new(RuleReference, sub_rule_id));
+                    pos = self.parse_space(pos + 1, is_nested);
+                }
+                _ => break,
+            }
+        }
+        Ok(pos)
+    }
+
+    fn parse_alternates(
+        &mut self,
+        mut pos: usize,
+        rule_name: &str,
+        rule_id: u32,
+        is_nested: bool,
+    ) -> Result<usize, WhisperError> {
+        let mut rule = Vec::new();
+        pos = self.parse_sequence(pos, rule_name, &mut rule, is_nested)?;
+        while self.at(pos) == Some('|') {
+            rule.push(WhisperGrammarElement::new(
+                WhisperGrammarElementType::Alternate,
+                0,
+            ));
+            pos = self.parse_space(pos + 1, true);
+            pos = self.parse_sequence(pos, rule_name, &mut rule, is_nested)?;
+        }
+        rule.push(WhisperGrammarElement::new(
+            WhisperGrammarElementType::End,
+            0,
+        ));
+        self.add_rule(rule_id, rule);
+        Ok(pos)
+    }
+
+    fn parse_rule(&mut self, pos: usize) -> Result<usize, WhisperError> {
+        let (name, name_end) = self.parse_name(pos)?;
+        let mut pos = self.parse_space(name_end, false);
+        let rule_id = self.get_symbol_id(&name);
+
+        if !self.src[pos..].starts_with(&[':', ':', '=']) {
+            return Self::error("expecting ::=", pos);
+        }
+        pos = self.parse_space(pos + 3, true);
+        pos = self.parse_alternates(pos, &name, rule_id, false)?;
+
+        match self.at(pos) {
+            Some('\r') => pos += if self.at(pos + 1) == Some('\n') { 2 } else { 1 },
+            Some('\n') => pos += 1,
+            Some(_) => return Self::error("expecting newline or end", pos),
+            None => {}
+        }
+        Ok(self.parse_space(pos, true))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use WhisperGrammarElementType::*;
+
+    fn e(element_type: WhisperGrammarElementType, value: u32) -> WhisperGrammarElement {
+        WhisperGrammarElement::new(element_type, value)
+    }
+
+    #[test]
+    fn test_parse_literals_and_alternates() {
+        let grammar =
+            WhisperGrammar::parse("root ::= \"ab\" | other # comment\nother ::= [0-9x]\n").unwrap();
+        assert_eq!(grammar.rule_index("root"), Some(0));
+        assert_eq!(grammar.rule_index("other"), Some(1));
+        assert_eq!(
+            grammar.rules(),
+            [
+                vec![
+                    e(Character, 'a' as u32),
+                    e(Character, 'b' as u32),
+                    e(Alternate, 0),
+                    e(RuleReference, 1),
+                    e(End, 0),
+                ],
+                vec![
+                    e(Character, '0' as u32),
+                    e(CharacterRangeUpper, '9' as u32),
+                    e(CharacterAlternate, 'x' as u32),
+                    e(End, 0),
+                ],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_repetition_and_groups() {
+        let grammar = WhisperGrammar::parse("root ::= (\"a\" | [^\\n])+").unwrap();
+        assert_eq!(
+            grammar.rules(),
+            [
+                vec![e(RuleReference, 2), e(End, 0)],
+                vec![
+                    e(Character, 'a' as u32),
+                    e(Alternate, 0),
+                    e(NotCharacter, '\n' as u32),
+                    e(End, 0),
+                ],
+                vec![
+                    e(RuleReference, 1),
+                    e(RuleReference, 2),
+                    e(Alternate, 0),
+                    e(RuleReference, 1),
+                    e(End, 0),
+                ],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(matches!(
+            WhisperGrammar::parse("root ::= missing"),
+            Err(WhisperError::InvalidGrammar {
+                reason: "undefined rule",
+                ..
+            })
+        ));
+        assert!(matches!(
+            WhisperGrammar::parse("root = \"a\""),
+            Err(WhisperError::InvalidGrammar { pos: 5, .. })
+        ));
+        assert!(WhisperGrammar::parse("root ::= \"a").is_err());
+        assert!(WhisperGrammar::parse("root ::= *").is_err());
+        assert!(WhisperGrammar::parse("root ::= (\"a\"").is_err());
+    }
+}
@@ -1,8 +1,14 @@
-use crate::whisper_grammar::WhisperGrammarElement;
+use crate::whisper_grammar::{GrammarRules, WhisperGrammar, WhisperGrammarElement};
 use crate::whisper_vad::WhisperVadParams;
-use std::ffi::{c_char, c_float, c_int, CString};
+use crate::{Language, WhisperError, WhisperSegmentData, WhisperTokenData, WhisperTokenId};
+use std::any::Any;
+use std::borrow::Cow;
+use std::ffi::{c_char, c_float, c_int, CStr, CString};
+use std::fmt;
 use std::marker::PhantomData;
-use std::sync::Arc;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use whisper_rs_sys::whisper_token;
 
 /// The sampling strategy to use to pick tokens from a list of likely possibilities.
@@ -36,17 +42,105 @@ pub struct SegmentCallbackData {
     pub text: String,
 }
 
+/// A progress update, see [`FullParams::set_progress_callback_with_eta`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Progress {
+    /// How far along the transcription is, from 0 to 100.
+    pub percent: i32,
+    /// Wall-clock time since [`crate::WhisperState::full`] was called.
+    pub elapsed: Duration,
+    /// A naive estimate of the time left, assuming the rest of the audio is processed at the same rate so far.
+    /// `None` until there's been some progress to estimate from.
+    pub eta: Option<Duration>,
+}
+
+impl Progress {
+    fn new(percent: i32, elapsed: Duration) -> Self {
+        let eta = (percent > 0).then(|| {
+            let remaining = 100u32.saturating_sub(percent as u32);
+            elapsed * remaining / percent as u32
+        });
+        Self {
+            percent,
+            elapsed,
+            eta,
+        }
+    }
+}
+
+/// Where the safe callbacks keep a panic until [`crate::WhisperState::full`] can rethrow it.
+///
+/// Unwinding out of an `extern "C"` callback into whisper.cpp is either undefined behavior or an abort,
+/// depending on the Rust version, so every safe callback is run through [`Self::catch`] instead.
+/// Once a callback has panicked, the other callbacks are skipped until the panic is taken.
+#[derive(Clone, Default)]
+pub(crate) struct CallbackPanic(Arc<Mutex<Option<Box<dyn Any + Send>>>>);
+
+impl CallbackPanic {
+    /// Run `f`, returning `on_panic` instead if it panics or an earlier callback already did.
+    fn catch<R>(&self, on_panic: R, f: impl FnOnce() -> R) -> R {
+        if self.lock().is_some() {
+            return on_panic;
+        }
+        match panic::catch_unwind(AssertUnwindSafe(f)) {
+            Ok(ret) => ret,
+            Err(payload) => {
+                self.lock().get_or_insert(payload);
+                on_panic
+            }
+        }
+    }
+
+    /// Take the panic payload of the first callback that panicked, if any.
+    pub(crate) fn take(&self) -> Option<Box<dyn Any + Send>> {
+        self.lock().take()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Option<Box<dyn Any + Send>>> {
+        // nothing can panic while the lock is held, but don't propagate poisoning regardless
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
 type SegmentCallbackFn = Box<dyn FnMut(SegmentCallbackData)>;
+type NewSegmentCallbackFn = Box<dyn FnMut(WhisperSegmentData)>;
 
 #[derive(Clone)]
 pub struct FullParams<'a, 'b> {
     pub(crate) fp: whisper_rs_sys::whisper_full_params,
     phantom_lang: PhantomData<&'a str>,
     phantom_tokens: PhantomData<&'b [c_int]>,
-    grammar: Option<Vec<whisper_rs_sys::whisper_grammar_element>>,
+    // shared rather than cloned, so clones of these params keep `fp.grammar_rules`,
+    // `fp.prompt_tokens`, `fp.language`, `fp.initial_prompt`, `fp.suppress_regex`
+    // and `fp.vad_model_path` valid
+    grammar: Option<Arc<GrammarRules>>,
+    prompt_tokens: Option<Arc<[WhisperTokenId]>>,
+    language: Option<Arc<CString>>,
+    initial_prompt: Option<Arc<CString>>,
+    suppress_regex: Option<Arc<CString>>,
+    vad_model_path: Option<Arc<CString>>,
     progress_callback_safe: Option<Arc<Box<dyn FnMut(i32)>>>,
+    // when the current `WhisperState::full` call started, for `set_progress_callback_with_eta`
+    progress_started: Option<Arc<Mutex<Instant>>>,
     abort_callback_safe: Option<Arc<Box<dyn FnMut() -> bool>>>,
     segment_calllback_safe: Option<Arc<SegmentCallbackFn>>,
+    // shared by all the safe callbacks, see `CallbackPanic`
+    pub(crate) callback_panic: CallbackPanic,
+}
+
+/// Greedy sampling with `best_of` set to 5, the same defaults as `whisper.cpp`'s own
+/// `whisper_full_default_params(WHISPER_SAMPLING_GREEDY)`.
+///
+/// # Examples
+/// ```
+/// # use whisper_rs::FullParams;
+/// let mut params = FullParams::default();
+/// params.set_language(Some("en"));
+/// ```
+impl Default for FullParams<'_, '_> {
+    fn default() -> Self {
+        Self::new(SamplingStrategy::Greedy { best_of: 5 })
+    }
 }
 
 impl<'a, 'b> FullParams<'a, 'b> {
@@ -85,12 +179,24 @@ impl<'a, 'b> FullParams<'a, 'b> {
             phantom_lang: PhantomData,
             phantom_tokens: PhantomData,
             grammar: None,
+            prompt_tokens: None,
+            language: None,
+            initial_prompt: None,
+            suppress_regex: None,
+            vad_model_path: None,
             progress_callback_safe: None,
+            progress_started: None,
             abort_callback_safe: None,
             segment_calllback_safe: None,
+            callback_panic: CallbackPanic::default(),
         }
     }
 
+    /// Start building parameters in a single expression, see [`FullParamsBuilder`].
+    pub fn builder(sampling_strategy: SamplingStrategy) -> FullParamsBuilder<'a, 'b> {
+        FullParamsBuilder::new(sampling_strategy)
+    }
+
     /// Set the number of threads to use for decoding.
     ///
     /// Defaults to min(4, std::thread::hardware_concurrency()).
@@ -100,6 +206,17 @@ impl<'a, 'b> FullParams<'a, 'b> {
 
     /// Max tokens to use from past text as prompt for the decoder
     ///
+    /// When transcribing audio longer than 30 seconds, the text of previous windows (and the initial prompt)
+    /// is fed back to the decoder as context. This caps how many of the most recent tokens are used.
+    /// Less context uses less compute per window and makes it less likely that a hallucination
+    /// or repetition loop carries over from one window to the next, at the cost of consistency
+    /// (e.g. of spelling and punctuation) across windows.
+    ///
+    /// whisper.cpp never uses more than half of the model's text context
+    /// ([`crate::WhisperContext::n_text_ctx`], so 224 tokens for every standard model),
+    /// so values above that have no effect. If [`Self::set_no_context`] is enabled,
+    /// no past text is used at all and this has no effect.
+    ///
     /// Defaults to 16384.
     pub fn set_n_max_text_ctx(&mut self, n_max_text_ctx: c_int) {
         self.fp.n_max_text_ctx = n_max_text_ctx;
@@ -107,12 +224,17 @@ impl<'a, 'b> FullParams<'a, 'b> {
 
     /// Set the start offset in milliseconds to use for decoding.
     ///
+    /// The offset is relative to the start of the samples passed to [`crate::WhisperState::full`],
+    /// so part of a long recording can be transcribed without slicing the samples yourself.
+    /// Segment timestamps stay relative to the start of the samples, not to the offset.
+    ///
     /// Defaults to 0.
     pub fn set_offset_ms(&mut self, offset_ms: c_int) {
         self.fp.offset_ms = offset_ms;
     }
 
-    /// Set the audio duration to process in milliseconds.
+    /// Set the audio duration to process in milliseconds, starting from [`Self::set_offset_ms`].
+    /// 0 processes everything up to the end of the samples.
     ///
     /// Defaults to 0.
     pub fn set_duration_ms(&mut self, duration_ms: c_int) {
@@ -128,6 +250,11 @@ impl<'a, 'b> FullParams<'a, 'b> {
 
     /// Do not use past transcription (if any) as initial prompt for the decoder.
     ///
+    /// Only the text from previous calls to [`crate::WhisperState::full`] on the same state is dropped:
+    /// tokens set with [`Self::set_prompt_tokens`] (or [`Self::set_initial_prompt`]) are still used.
+    /// So for chunked transcription, enable this and pass the tokens that should carry over
+    /// as prompt tokens, to control exactly which context each chunk sees.
+    ///
     /// Defaults to false.
     pub fn set_no_context(&mut self, no_context: bool) {
         self.fp.no_context = no_context;
@@ -142,6 +269,10 @@ impl<'a, 'b> FullParams<'a, 'b> {
 
     /// Force single segment output. This may be useful for streaming.
     ///
+    /// All of the audio passed to [`crate::WhisperState::full`] is transcribed into one segment,
+    /// instead of being split at the timestamp tokens the model produces.
+    /// This works best with short audio, as a single segment can only hold a limited number of tokens.
+    ///
     /// Defaults to false.
     pub fn set_single_segment(&mut self, single_segment: bool) {
         self.fp.single_segment = single_segment;
@@ -182,6 +313,27 @@ impl<'a, 'b> FullParams<'a, 'b> {
     ///
     /// Enable token-level timestamps.
     ///
+    /// These are the `t0`/`t1` timestamps of [`crate::WhisperToken::token_data`], estimated from the
+    /// timestamp token probabilities. They're independent of DTW timestamps (`t_dtw`),
+    /// which are enabled on the context instead, but both can be used together.
+    ///
+    /// # Examples
+    /// ```
+    /// # use whisper_rs::{DtwMode, DtwModelPreset, FullParams, SamplingStrategy, WhisperContextParameters};
+    /// let mut context_params = WhisperContextParameters::default();
+    /// context_params.dtw_parameters.mode = DtwMode::ModelPreset {
+    ///     model_preset: DtwModelPreset::BaseEn,
+    /// };
+    ///
+    /// let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+    /// params.set_token_timestamps(true);
+    /// // only split at timestamp tokens the model is fairly sure of
+    /// params.set_thold_pt(0.05);
+    /// params.set_thold_ptsum(0.05);
+    /// // keep segments short, so there are fewer tokens to spread across each one
+    /// params.set_max_tokens(32);
+    /// ```
+    ///
     /// Defaults to false.
     pub fn set_token_timestamps(&mut self, token_timestamps: bool) {
         self.fp.token_timestamps = token_timestamps;
@@ -191,6 +343,10 @@ impl<'a, 'b> FullParams<'a, 'b> {
     ///
     /// Set timestamp token probability threshold.
     ///
+    /// With [`Self::set_token_timestamps`], a token only gets its own timestamp if the probability
+    /// of the most likely timestamp token at that point is above this.
+    /// The timestamps of other tokens are estimated from their neighbours.
+    ///
     /// Defaults to 0.01.
     pub fn set_thold_pt(&mut self, thold_pt: f32) {
         self.fp.thold_pt = thold_pt;
@@ -200,6 +356,8 @@ impl<'a, 'b> FullParams<'a, 'b> {
     ///
     /// Set timestamp token sum probability threshold.
     ///
+    /// Like [`Self::set_thold_pt`], but for the summed probability of all timestamp tokens.
+    ///
     /// Defaults to 0.01.
     pub fn set_thold_ptsum(&mut self, thold_ptsum: f32) {
         self.fp.thold_ptsum = thold_ptsum;
@@ -207,7 +365,11 @@ impl<'a, 'b> FullParams<'a, 'b> {
 
     /// # EXPERIMENTAL
     ///
-    /// Set maximum segment length in characters.
+    /// Set maximum segment length in characters. 0 means no limit, and 1 splits every word into its own segment.
+    ///
+    /// This only takes effect if [`Self::set_token_timestamps`] is enabled,
+    /// as the timestamps of the new segments come from the token timestamps.
+    /// Segments are split between tokens, so use [`Self::set_split_on_word`] to avoid splitting words.
     ///
     /// Defaults to 0.
     pub fn set_max_len(&mut self, max_len: c_int) {
@@ -218,6 +380,9 @@ impl<'a, 'b> FullParams<'a, 'b> {
     ///
     /// Should the timestamps be split on words instead of characters?
     ///
+    /// When splitting segments longer than [`Self::set_max_len`], only split them at word boundaries.
+    /// Like `max_len`, this requires [`Self::set_token_timestamps`].
+    ///
     /// Defaults to false.
     pub fn set_split_on_word(&mut self, split_on_word: bool) {
         self.fp.split_on_word = split_on_word;
@@ -245,6 +410,16 @@ impl<'a, 'b> FullParams<'a, 'b> {
     ///
     /// Overwrite the audio context size. 0 = default.
     ///
+    /// The encoder always processes a full 30 second window, made up of
+    /// [`crate::WhisperContext::model_n_audio_ctx`] positions (1500 for every standard model),
+    /// so each position covers 20 ms of audio. For short clips, most of that window is padding;
+    /// setting a smaller audio context only encodes the start of the window, which makes
+    /// the encoder much faster. For a clip of `n` seconds, use at least `n * 50` positions.
+    ///
+    /// **Warning**: audio past the end of the reduced context is ignored, so a value that's too small
+    /// silently truncates the transcription. The models were trained on the full context,
+    /// so accuracy also tends to drop at very small values, and hallucinations become more likely.
+    ///
     /// Defaults to 0.
     pub fn set_audio_ctx(&mut self, audio_ctx: c_int) {
         self.fp.audio_ctx = audio_ctx;
@@ -255,6 +430,10 @@ impl<'a, 'b> FullParams<'a, 'b> {
     /// Enable tinydiarize support.
     /// Experimental speaker turn detection.
     ///
+    /// This requires a tinydiarize model (such as `ggml-small.en-tdrz.bin`), and populates
+    /// [`crate::WhisperSegment::next_segment_speaker_turn`], which is otherwise always false.
+    /// See `examples/diarization.rs`.
+    ///
     /// Defaults to false.
     pub fn set_tdrz_enable(&mut self, tdrz_enable: bool) {
         self.fp.tdrz_enable = tdrz_enable;
@@ -277,23 +456,55 @@ impl<'a, 'b> FullParams<'a, 'b> {
         self.fp.prompt_n_tokens = tokens_len;
     }
 
+    /// Set tokens to provide the model as initial input, such as the tokens of the previous chunk
+    /// when transcribing audio in chunks, to carry context across them without re-tokenizing.
+    ///
+    /// Unlike [`Self::set_tokens`], the tokens are copied and kept alive for as long as these parameters
+    /// (and any clones of them), so they don't need to outlive the call to [`crate::WhisperState::full`].
+    /// See [`crate::WhisperContext::tokenize_auto`] to get tokens from text.
+    ///
+    /// These tokens are prepended to any existing text content from a previous call,
+    /// unless [`Self::set_no_context`] is enabled. Calling this (or [`Self::set_tokens`])
+    /// more than once will overwrite the previous tokens.
+    ///
+    /// Defaults to an empty vector.
+    pub fn set_prompt_tokens(&mut self, tokens: &[WhisperTokenId]) {
+        let tokens: Arc<[WhisperTokenId]> = Arc::from(tokens);
+        self.fp.prompt_tokens = tokens.as_ptr();
+        self.fp.prompt_n_tokens = tokens.len() as c_int;
+        self.prompt_tokens = Some(tokens);
+    }
+
     /// Set the target language.
     ///
     /// For auto-detection, set this to either "auto" or None.
+    /// Use [`crate::get_lang_id`] to check whether a language is supported.
     ///
     /// Defaults to "en".
     pub fn set_language(&mut self, language: Option<&'a str>) {
-        self.fp.language = match language {
-            Some(language) => CString::new(language)
-                .expect("Language contains null byte")
-                .into_raw() as *const _,
-            None => std::ptr::null(),
-        };
+        self.language = language
+            .map(|language| Arc::new(CString::new(language).expect("Language contains null byte")));
+        self.fp.language = self
+            .language
+            .as_ref()
+            .map_or(std::ptr::null(), |language| language.as_ptr());
+    }
+
+    /// Set the target language, from a [`Language`] rather than a string.
+    ///
+    /// For auto-detection, set this to None.
+    /// See [`Self::set_language`].
+    pub fn set_language_typed(&mut self, language: Option<Language>) {
+        self.set_language(language.map(Language::as_code));
     }
 
     /// Set `detect_language`.
     ///
-    /// Has the same effect as setting the language to "auto" or None.
+    /// When enabled, [`crate::WhisperState::full`] only detects the language and returns
+    /// without transcribing anything, which is much cheaper than a full transcription.
+    /// The detected language is then available from [`crate::WhisperState::full_lang_id_from_state`].
+    ///
+    /// To detect the language *and* transcribe, set the language to "auto" or None instead.
     ///
     /// Defaults to false.
     pub fn set_detect_language(&mut self, detect_language: bool) {
@@ -304,6 +515,9 @@ impl<'a, 'b> FullParams<'a, 'b> {
     /// See <https://github.com/openai/whisper/blob/f82bc59f5ea234d4b97fb2860842ed38519f7e65/whisper/decoding.py#L89>
     /// for more information.
     ///
+    /// When enabled, a segment can't start with a blank (a lone space) or end of text,
+    /// which stops the decoder from producing empty segments on quiet audio.
+    ///
     /// Defaults to true.
     pub fn set_suppress_blank(&mut self, suppress_blank: bool) {
         self.fp.suppress_blank = suppress_blank;
@@ -313,14 +527,44 @@ impl<'a, 'b> FullParams<'a, 'b> {
     /// See <https://github.com/openai/whisper/blob/7858aa9c08d98f75575035ecd6481f462d66ca27/whisper/tokenizer.py#L224-L253>
     /// for more information.
     ///
+    /// When enabled, tokens for symbols that aren't spoken (such as `[`, `(`, `♪` and `*`) are never sampled.
+    /// This cuts down on hallucinated annotations like "(music)" or "[BLANK_AUDIO]"
+    /// on noisy audio or audio with music, at the cost of also suppressing any of those symbols
+    /// that should legitimately appear in the transcript.
+    ///
     /// Defaults to false.
     pub fn set_suppress_nst(&mut self, suppress_nst: bool) {
         self.fp.suppress_nst = suppress_nst;
     }
 
+    /// Set a regular expression of tokens to never sample, or `None` to suppress nothing.
+    ///
+    /// The regex uses the ECMAScript dialect of C++'s `std::regex`, and must match the *whole* text
+    /// of a token (`std::regex_match`) to suppress it. Token text usually includes its leading space,
+    /// so `" ?[Tt]hanks?"` suppresses both `" Thank"` and `"thanks"`.
+    /// As tokens are matched one at a time, this can't suppress a multi-token phrase as a whole.
+    ///
+    /// The regex is kept alive for as long as these parameters (and any clones of them).
+    ///
+    /// # Panics
+    /// This method will panic if `regex` contains a null byte.
+    ///
+    /// Defaults to None.
+    pub fn set_suppress_regex(&mut self, regex: Option<&str>) {
+        self.suppress_regex = regex
+            .map(|regex| Arc::new(CString::new(regex).expect("Suppress regex contains null byte")));
+        self.fp.suppress_regex = self
+            .suppress_regex
+            .as_ref()
+            .map_or(std::ptr::null(), |regex| regex.as_ptr());
+    }
+
     /// Set initial decoding temperature.
     /// See <https://ai.stackexchange.com/a/32478> for more information.
     ///
+    /// If decoding at this temperature fails [`Self::set_entropy_thold`] or [`Self::set_logprob_thold`],
+    /// the segment is decoded again at a temperature [`Self::set_temperature_inc`] higher, up to 1.0.
+    ///
     /// Defaults to 0.0.
     pub fn set_temperature(&mut self, temperature: f32) {
         self.fp.temperature = temperature;
@@ -348,6 +592,8 @@ impl<'a, 'b> FullParams<'a, 'b> {
     /// See <https://github.com/openai/whisper/blob/f82bc59f5ea234d4b97fb2860842ed38519f7e65/whisper/transcribe.py#L274-L278>
     /// for more information.
     ///
+    /// Set to 0.0 to disable temperature fallback entirely.
+    ///
     /// Defaults to 0.2.
     pub fn set_temperature_inc(&mut self, temperature_inc: f32) {
         self.fp.temperature_inc = temperature_inc;
@@ -356,6 +602,9 @@ impl<'a, 'b> FullParams<'a, 'b> {
     /// Set entropy_thold. Similar to OpenAI's compression_ratio_threshold.
     /// See <https://github.com/openai/whisper/blob/f82bc59f5ea234d4b97fb2860842ed38519f7e65/whisper/transcribe.py#L274-L278> for more information.
     ///
+    /// Decoded text whose token entropy is below this is considered repetitive,
+    /// and triggers a temperature fallback (see [`Self::set_temperature`]).
+    ///
     /// Defaults to 2.4.
     pub fn set_entropy_thold(&mut self, entropy_thold: f32) {
         self.fp.entropy_thold = entropy_thold;
@@ -365,12 +614,19 @@ impl<'a, 'b> FullParams<'a, 'b> {
     /// See <https://github.com/openai/whisper/blob/f82bc59f5ea234d4b97fb2860842ed38519f7e65/whisper/transcribe.py#L274-L278>
     /// for more information.
     ///
+    /// Decoded text whose average token log probability is below this
+    /// triggers a temperature fallback (see [`Self::set_temperature`]).
+    ///
     /// Defaults to -1.0.
     pub fn set_logprob_thold(&mut self, logprob_thold: f32) {
         self.fp.logprob_thold = logprob_thold;
     }
 
-    /// Set no_speech_thold. Currently (as of v1.3.0) not implemented.
+    /// Set no_speech_thold.
+    ///
+    /// A segment is treated as silence, and its text discarded, if its no_speech probability
+    /// (see [`crate::WhisperSegment::no_speech_probability`]) is above this
+    /// and its average token log probability is below [`Self::set_logprob_thold`].
     ///
     /// Defaults to 0.6.
     pub fn set_no_speech_thold(&mut self, no_speech_thold: f32) {
@@ -457,7 +713,9 @@ impl<'a, 'b> FullParams<'a, 'b> {
         }
 
         match closure.into() {
-            Some(closure) => {
+            Some(mut closure) => {
+                let guard = self.callback_panic.clone();
+                let closure = move |data| guard.catch((), || closure(data));
                 // Stable address
                 let closure = Box::new(closure) as SegmentCallbackFn;
                 // Thin pointer
@@ -523,7 +781,9 @@ impl<'a, 'b> FullParams<'a, 'b> {
         }
 
         match closure.into() {
-            Some(closure) => {
+            Some(mut closure) => {
+                let guard = self.callback_panic.clone();
+                let closure = move |data| guard.catch((), || closure(data));
                 // Stable address
                 let closure = Box::new(closure) as SegmentCallbackFn;
                 // Thin pointer
@@ -543,6 +803,72 @@ impl<'a, 'b> FullParams<'a, 'b> {
         }
     }
 
+    /// Set the callback for new segments, receiving an owned copy of each segment as it's finalized.
+    ///
+    /// Unlike [`Self::set_segment_callback_safe`], this also provides the tokens,
+    /// no_speech probability and speaker turn of each segment. Invalid UTF-8 in the text is replaced with the replacement character.
+    ///
+    /// This replaces any other new segment callback, and is replaced by them.
+    ///
+    /// # Reentrancy
+    /// The callback runs synchronously on the thread that called [`crate::WhisperState::full`],
+    /// in the middle of decoding. The state is not accessible from the callback, and decoding
+    /// doesn't continue until it returns: send the segments elsewhere (e.g. over a channel)
+    /// rather than doing slow work in it.
+    ///
+    /// **Warning** Can't be used with DTW. DTW will produce inconsistent callback invocation
+    ///
+    /// Defaults to None.
+    pub fn set_new_segment_callback_safe<O, F>(&mut self, closure: O)
+    where
+        F: FnMut(WhisperSegmentData) + 'static,
+        O: Into<Option<F>>,
+    {
+        use std::ffi::c_void;
+        use whisper_rs_sys::{whisper_context, whisper_state};
+
+        extern "C" fn trampoline(
+            ctx: *mut whisper_context,
+            state: *mut whisper_state,
+            n_new: i32,
+            user_data: *mut c_void,
+        ) {
+            unsafe {
+                let user_data = &mut *(user_data as *mut NewSegmentCallbackFn);
+                let n_segments = whisper_rs_sys::whisper_full_n_segments_from_state(state);
+                let s0 = n_segments - n_new;
+
+                for i in s0..n_segments {
+                    if let Ok(segment) = WhisperSegmentData::from_raw(ctx, state, i) {
+                        user_data(segment);
+                    }
+                }
+            }
+        }
+
+        match closure.into() {
+            Some(mut closure) => {
+                let guard = self.callback_panic.clone();
+                let closure = move |segment| guard.catch((), || closure(segment));
+                // Stable address
+                let closure = Box::new(closure) as NewSegmentCallbackFn;
+                // Thin pointer
+                let closure = Box::new(closure);
+                // Raw pointer
+                let closure = Box::into_raw(closure);
+
+                self.fp.new_segment_callback_user_data = closure as *mut c_void;
+                self.fp.new_segment_callback = Some(trampoline);
+                self.segment_calllback_safe = None;
+            }
+            None => {
+                self.segment_calllback_safe = None;
+                self.fp.new_segment_callback = None;
+                self.fp.new_segment_callback_user_data = std::ptr::null_mut::<c_void>();
+            }
+        }
+    }
+
     /// Set the callback for progress updates.
     ///
     /// Note that is still a C callback.
@@ -589,7 +915,9 @@ impl<'a, 'b> FullParams<'a, 'b> {
         }
 
         match closure.into() {
-            Some(closure) => {
+            Some(mut closure) => {
+                let guard = self.callback_panic.clone();
+                let closure = move |progress| guard.catch((), || closure(progress));
                 self.fp.progress_callback = Some(trampoline::<Box<dyn FnMut(i32)>>);
                 let boxed_closure = Box::new(closure) as Box<dyn FnMut(i32)>;
                 let boxed_closure = Box::new(boxed_closure);
@@ -603,6 +931,52 @@ impl<'a, 'b> FullParams<'a, 'b> {
                 self.progress_callback_safe = None;
             }
         }
+        self.progress_started = None;
+    }
+
+    /// Set the callback for progress updates, with the elapsed time and an estimate of the time left.
+    ///
+    /// This replaces any callback set with [`Self::set_progress_callback_safe`].
+    /// Time is measured from the start of each [`crate::WhisperState::full`] call these params are used for.
+    ///
+    /// # Examples
+    /// ```
+    /// # use whisper_rs::{FullParams, SamplingStrategy};
+    /// let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+    /// params.set_progress_callback_with_eta(|progress| match progress.eta {
+    ///     Some(eta) => println!("{}% done, {:.0?} left", progress.percent, eta),
+    ///     None => println!("{}% done", progress.percent),
+    /// });
+    /// ```
+    ///
+    /// Defaults to None.
+    pub fn set_progress_callback_with_eta<F>(&mut self, mut closure: F)
+    where
+        F: FnMut(Progress) + 'static,
+    {
+        let started = Arc::new(Mutex::new(Instant::now()));
+        let elapsed = started.clone();
+        self.set_progress_callback_safe(move |percent| {
+            let elapsed = elapsed.lock().unwrap_or_else(|e| e.into_inner()).elapsed();
+            closure(Progress::new(percent, elapsed))
+        });
+        self.progress_started = Some(started);
+    }
+
+    /// Whether any callback is set, safe or not.
+    pub(crate) fn has_callbacks(&self) -> bool {
+        self.fp.new_segment_callback.is_some()
+            || self.fp.progress_callback.is_some()
+            || self.fp.encoder_begin_callback.is_some()
+            || self.fp.logits_filter_callback.is_some()
+            || self.fp.abort_callback.is_some()
+    }
+
+    /// Restart the clock for [`Self::set_progress_callback_with_eta`], if it's set.
+    pub(crate) fn start_progress_timer(&self) {
+        if let Some(started) = &self.progress_started {
+            *started.lock().unwrap_or_else(|e| e.into_inner()) = Instant::now();
+        }
     }
 
     /// Set the callback for abort conditions, potentially using a closure.
@@ -613,6 +987,24 @@ impl<'a, 'b> FullParams<'a, 'b> {
     /// See `set_progress_callback` if you need to use `whisper_context` and `whisper_state`,
     /// or extend this one to support their use.
     ///
+    /// The callback is polled while the encoder and decoder run,
+    /// and [`crate::WhisperState::full`] returns an error shortly after it returns `true`.
+    /// If the closure panics, it's treated as returning `true`, and the panic is resumed by
+    /// [`crate::WhisperState::full`].
+    ///
+    /// # Examples
+    /// ```
+    /// # use whisper_rs::{FullParams, SamplingStrategy};
+    /// use std::sync::atomic::{AtomicBool, Ordering};
+    /// use std::sync::Arc;
+    ///
+    /// let stop = Arc::new(AtomicBool::new(false));
+    /// let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+    /// let stop_flag = stop.clone();
+    /// params.set_abort_callback_safe(move || stop_flag.load(Ordering::Relaxed));
+    /// // call `stop.store(true, Ordering::Relaxed)` from another thread to cancel a running `full`
+    /// ```
+    ///
     /// Defaults to None.
     pub fn set_abort_callback_safe<O, F>(&mut self, closure: O)
     where
@@ -630,7 +1022,10 @@ impl<'a, 'b> FullParams<'a, 'b> {
         }
 
         match closure.into() {
-            Some(closure) => {
+            Some(mut closure) => {
+                // a panicking callback aborts the transcription
+                let guard = self.callback_panic.clone();
+                let closure = move || guard.catch(true, &mut closure);
                 // Stable address
                 let closure = Box::new(closure) as Box<dyn FnMut() -> bool>;
                 // Thin pointer
@@ -638,7 +1033,7 @@ impl<'a, 'b> FullParams<'a, 'b> {
                 // Raw pointer
                 let closure = Box::into_raw(closure);
 
-                self.fp.abort_callback = Some(trampoline::<F>);
+                self.fp.abort_callback = Some(trampoline::<Box<dyn FnMut() -> bool>>);
                 self.fp.abort_callback_user_data = closure as *mut c_void;
                 self.abort_callback_safe = None;
             }
@@ -691,6 +1086,58 @@ impl<'a, 'b> FullParams<'a, 'b> {
         self.fp.encoder_begin_callback_user_data = user_data;
     }
 
+    /// Set the callback that is called each time before the encoder begins, potentially using a closure.
+    ///
+    /// The encoder runs once per 30 second window of audio, before that window is decoded.
+    /// Returning `false` stops the transcription before encoding, while returning `true` continues as normal.
+    /// When stopped, [`crate::WhisperState::full`] still returns `Ok`, with the segments of any earlier windows.
+    /// If the closure panics, it's treated as returning `false`, and the panic is resumed by
+    /// [`crate::WhisperState::full`] instead.
+    /// Note that the closure can't inspect the encoder output, as it hasn't been computed yet.
+    ///
+    /// The closure is called on the thread running [`crate::WhisperState::full`], and never reentrantly.
+    /// As [`FullParams`] is [`Send`] whatever the closure captures, the closure must be safe to call
+    /// from another thread (as if it were [`Send`]) if you move these parameters across threads.
+    ///
+    /// Defaults to None.
+    pub fn set_encoder_begin_callback_safe<O, F>(&mut self, closure: O)
+    where
+        F: FnMut() -> bool + 'static,
+        O: Into<Option<F>>,
+    {
+        use std::ffi::c_void;
+        use whisper_rs_sys::{whisper_context, whisper_state};
+
+        unsafe extern "C" fn trampoline<F>(
+            _: *mut whisper_context,
+            _: *mut whisper_state,
+            user_data: *mut c_void,
+        ) -> bool
+        where
+            F: FnMut() -> bool,
+        {
+            let user_data = &mut *(user_data as *mut F);
+            user_data()
+        }
+
+        match closure.into() {
+            Some(mut closure) => {
+                // a panicking callback stops the transcription before encoding
+                let guard = self.callback_panic.clone();
+                let closure = move || guard.catch(false, &mut closure);
+                let closure = Box::new(closure) as Box<dyn FnMut() -> bool>;
+                let closure = Box::into_raw(Box::new(closure));
+
+                self.fp.encoder_begin_callback = Some(trampoline::<Box<dyn FnMut() -> bool>>);
+                self.fp.encoder_begin_callback_user_data = closure as *mut c_void;
+            }
+            None => {
+                self.fp.encoder_begin_callback = None;
+                self.fp.encoder_begin_callback_user_data = std::ptr::null_mut::<c_void>();
+            }
+        }
+    }
+
     /// Set the callback that is called by each decoder to filter obtained logits.
     ///
     /// Note that this callback has not been Rustified yet (and likely never will be, unless someone else feels the need to do so).
@@ -722,6 +1169,69 @@ impl<'a, 'b> FullParams<'a, 'b> {
         self.fp.logits_filter_callback_user_data = user_data;
     }
 
+    /// Set the callback that can modify the logits before each token is sampled, potentially using a closure.
+    ///
+    /// The closure receives the tokens decoded so far in the current segment, and the logits
+    /// of every token in the vocabulary, indexed by token ID.
+    /// The logits slice is always [`crate::WhisperContext::n_vocab`] long.
+    /// Set a logit to `f32::NEG_INFINITY` to forbid a token, or add to it to make a token more likely.
+    ///
+    /// Never write NaN to a logit: whisper.cpp doesn't check for it, and NaNs propagate through
+    /// the softmax into every probability, corrupting the whole decode.
+    ///
+    /// Like [`Self::set_encoder_begin_callback_safe`], the closure is called on the thread running
+    /// [`crate::WhisperState::full`], and must be safe to call from another thread if you move these
+    /// parameters across threads.
+    ///
+    /// Defaults to None.
+    pub fn set_logits_filter_callback_safe<O, F>(&mut self, closure: O)
+    where
+        F: FnMut(&[WhisperTokenData], &mut [f32]) + 'static,
+        O: Into<Option<F>>,
+    {
+        use std::ffi::c_void;
+        use whisper_rs_sys::{whisper_context, whisper_state, whisper_token_data};
+
+        type LogitsFilterFn = Box<dyn FnMut(&[WhisperTokenData], &mut [f32])>;
+
+        unsafe extern "C" fn trampoline(
+            ctx: *mut whisper_context,
+            _: *mut whisper_state,
+            tokens: *const whisper_token_data,
+            n_tokens: c_int,
+            logits: *mut f32,
+            user_data: *mut c_void,
+        ) {
+            let user_data = &mut *(user_data as *mut LogitsFilterFn);
+            let tokens = if tokens.is_null() || n_tokens <= 0 {
+                &[]
+            } else {
+                std::slice::from_raw_parts(tokens, n_tokens as usize)
+            };
+            let n_vocab = whisper_rs_sys::whisper_n_vocab(ctx);
+            let logits = std::slice::from_raw_parts_mut(logits, n_vocab.max(0) as usize);
+            user_data(tokens, logits);
+        }
+
+        match closure.into() {
+            Some(mut closure) => {
+                let guard = self.callback_panic.clone();
+                let closure = move |tokens: &[WhisperTokenData], logits: &mut [f32]| {
+                    guard.catch((), || closure(tokens, logits))
+                };
+                let closure = Box::new(closure) as LogitsFilterFn;
+                let closure = Box::into_raw(Box::new(closure));
+
+                self.fp.logits_filter_callback = Some(trampoline);
+                self.fp.logits_filter_callback_user_data = closure as *mut c_void;
+            }
+            None => {
+                self.fp.logits_filter_callback = None;
+                self.fp.logits_filter_callback_user_data = std::ptr::null_mut::<c_void>();
+            }
+        }
+    }
+
     /// Set the callback that is called each time before ggml computation starts.
     ///
     /// Note that this callback has not been Rustified yet (and likely never will be, unless someone else feels the need to do so).
@@ -749,20 +1259,19 @@ impl<'a, 'b> FullParams<'a, 'b> {
 
     /// Enable an array of grammar elements to be passed to the whisper model.
     ///
+    /// `grammar` is every rule concatenated, each ending with a [`crate::WhisperGrammarElementType::End`]
+    /// element. Rules are numbered in order from 0, which is what
+    /// [`crate::WhisperGrammarElementType::RuleReference`] elements and [`Self::set_start_rule`] refer to.
+    /// To write the grammar as GBNF instead, see [`Self::set_gbnf_grammar`].
+    ///
     /// Defaults to an empty vector.
     pub fn set_grammar(&mut self, grammar: Option<&[WhisperGrammarElement]>) {
         if let Some(grammar) = grammar {
-            // convert to c types
-            let inner = grammar.iter().map(|e| e.to_c_type()).collect::<Vec<_>>();
-            // turn into ptr and len
-            let grammar_ptr = inner.as_ptr() as *mut _;
-            let grammar_len = inner.len();
-
-            self.grammar = Some(inner);
-
-            // set the grammar
-            self.fp.grammar_rules = grammar_ptr;
-            self.fp.n_grammar_rules = grammar_len;
+            let rules = GrammarRules::new(
+                grammar
+                    .split_inclusive(|e| e.element_type == crate::WhisperGrammarElementType::End),
+            );
+            self.set_grammar_rules(rules);
         } else {
             self.grammar = None;
             self.fp.grammar_rules = std::ptr::null_mut();
@@ -771,6 +1280,35 @@ impl<'a, 'b> FullParams<'a, 'b> {
         }
     }
 
+    /// Constrain decoding to a grammar parsed with [`WhisperGrammar::parse`], starting at the rule called `start_rule`
+    /// (usually `"root"`).
+    ///
+    /// Use [`Self::set_grammar_penalty`] to control how strongly the grammar is enforced.
+    ///
+    /// # Returns
+    /// Ok(()) on success, Err([`WhisperError::InvalidGrammar`]) if `grammar` has no rule called `start_rule`.
+    pub fn set_gbnf_grammar(
+        &mut self,
+        grammar: &WhisperGrammar,
+        start_rule: &str,
+    ) -> Result<(), WhisperError> {
+        let start_rule = grammar
+            .rule_index(start_rule)
+            .ok_or(WhisperError::InvalidGrammar {
+                reason: "undefined start rule",
+                pos: 0,
+            })?;
+        self.set_grammar_rules(GrammarRules::new(grammar.rules().iter().map(Vec::as_slice)));
+        self.fp.i_start_rule = start_rule;
+        Ok(())
+    }
+
+    fn set_grammar_rules(&mut self, rules: GrammarRules) {
+        self.fp.grammar_rules = rules.as_ptr();
+        self.fp.n_grammar_rules = rules.len();
+        self.grammar = Some(Arc::new(rules));
+    }
+
     /// Set the start grammar rule. Does nothing if no grammar is set.
     ///
     /// Defaults to 0.
@@ -806,13 +1344,19 @@ impl<'a, 'b> FullParams<'a, 'b> {
     /// // ... further usage of params ...
     /// ```
     pub fn set_initial_prompt(&mut self, initial_prompt: &str) {
-        self.fp.initial_prompt = CString::new(initial_prompt)
-            .expect("Initial prompt contains null byte")
-            .into_raw() as *const c_char;
+        let initial_prompt =
+            Arc::new(CString::new(initial_prompt).expect("Initial prompt contains null byte"));
+        self.fp.initial_prompt = initial_prompt.as_ptr();
+        self.initial_prompt = Some(initial_prompt);
     }
 
     /// Enable or disable VAD.
     ///
+    /// When enabled, [`crate::WhisperState::full`] runs the VAD model set with
+    /// [`Self::set_vad_model_path`] first, and only transcribes the detected speech.
+    /// Timestamps are still relative to the start of the original audio.
+    /// This is simpler than running a [`crate::WhisperVadContext`] and transcribing its output yourself.
+    ///
     /// # Panics
     /// This method will panic if `vad_model_path` is not set prior to enabling VAD.
     pub fn enable_vad(&mut self, vad: bool) {
@@ -825,26 +1369,246 @@ impl<'a, 'b> FullParams<'a, 'b> {
 
     /// Set the path where a VAD model can be found. Passing `None` will clear it and disable VAD.
     ///
+    /// The path is kept alive for as long as these parameters (and any clones of them).
+    ///
     /// # Panics
     /// This method will panic if `vad_model_path` contains a null byte.
     pub fn set_vad_model_path(&mut self, vad_model_path: Option<&str>) {
-        self.fp.vad_model_path = if let Some(vad_model_path) = vad_model_path {
-            CString::new(vad_model_path)
-                .expect("VAD model path contains null byte")
-                .into_raw() as *const c_char
-        } else {
-            self.fp.vad = false;
+        self.vad_model_path = vad_model_path.map(|vad_model_path| {
+            Arc::new(CString::new(vad_model_path).expect("VAD model path contains null byte"))
+        });
+        self.fp.vad_model_path = match &self.vad_model_path {
+            Some(vad_model_path) => vad_model_path.as_ptr(),
+            None => {
+                self.fp.vad = false;
 
-            std::ptr::null()
+                std::ptr::null()
+            }
         };
     }
 
-    /// Replace the VAD model parameters.
+    /// Replace the VAD model parameters, used when VAD is enabled with [`Self::enable_vad`].
     pub fn set_vad_params(&mut self, params: WhisperVadParams) {
         self.fp.vad_params = params.into_inner();
     }
 }
 
+/// Prints every primitive parameter, with strings decoded and callbacks shown as `<callback>`.
+impl fmt::Debug for FullParams<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        struct Callback;
+        impl fmt::Debug for Callback {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("<callback>")
+            }
+        }
+
+        fn string(ptr: *const c_char) -> Option<Cow<'static, str>> {
+            // SAFETY: string pointers are either null, static defaults from whisper.cpp,
+            // or point into a CString kept alive by these params
+            (!ptr.is_null()).then(|| unsafe { CStr::from_ptr(ptr) }.to_string_lossy())
+        }
+
+        let fp = &self.fp;
+        let strategy = if fp.strategy
+            == whisper_rs_sys::whisper_sampling_strategy_WHISPER_SAMPLING_GREEDY as _
+        {
+            SamplingStrategy::Greedy {
+                best_of: fp.greedy.best_of,
+            }
+        } else {
+            SamplingStrategy::BeamSearch {
+                beam_size: fp.beam_search.beam_size,
+                patience: fp.beam_search.patience,
+            }
+        };
+        let prompt_tokens: &[c_int] = if fp.prompt_tokens.is_null() {
+            &[]
+        } else {
+            // SAFETY: set either from a slice borrowed for 'b by `set_tokens`, or from
+            // `self.prompt_tokens` by `set_prompt_tokens`, which is kept alive as long as `self`
+            unsafe { std::slice::from_raw_parts(fp.prompt_tokens, fp.prompt_n_tokens as usize) }
+        };
+
+        f.debug_struct("FullParams")
+            .field("strategy", &strategy)
+            .field("n_threads", &fp.n_threads)
+            .field("n_max_text_ctx", &fp.n_max_text_ctx)
+            .field("offset_ms", &fp.offset_ms)
+            .field("duration_ms", &fp.duration_ms)
+            .field("translate", &fp.translate)
+            .field("no_context", &fp.no_context)
+            .field("no_timestamps", &fp.no_timestamps)
+            .field("single_segment", &fp.single_segment)
+            .field("print_special", &fp.print_special)
+            .field("print_progress", &fp.print_progress)
+            .field("print_realtime", &fp.print_realtime)
+            .field("print_timestamps", &fp.print_timestamps)
+            .field("token_timestamps", &fp.token_timestamps)
+            .field("thold_pt", &fp.thold_pt)
+            .field("thold_ptsum", &fp.thold_ptsum)
+            .field("max_len", &fp.max_len)
+            .field("split_on_word", &fp.split_on_word)
+            .field("max_tokens", &fp.max_tokens)
+            .field("debug_mode", &fp.debug_mode)
+            .field("audio_ctx", &fp.audio_ctx)
+            .field("tdrz_enable", &fp.tdrz_enable)
+            .field("suppress_regex", &string(fp.suppress_regex))
+            .field("initial_prompt", &string(fp.initial_prompt))
+            .field("prompt_tokens", &prompt_tokens)
+            .field("language", &string(fp.language))
+            .field("detect_language", &fp.detect_language)
+            .field("suppress_blank", &fp.suppress_blank)
+            .field("suppress_nst", &fp.suppress_nst)
+            .field("temperature", &fp.temperature)
+            .field("max_initial_ts", &fp.max_initial_ts)
+            .field("length_penalty", &fp.length_penalty)
+            .field("temperature_inc", &fp.temperature_inc)
+            .field("entropy_thold", &fp.entropy_thold)
+            .field("logprob_thold", &fp.logprob_thold)
+            .field("no_speech_thold", &fp.no_speech_thold)
+            .field(
+                "new_segment_callback",
+                &fp.new_segment_callback.map(|_| Callback),
+            )
+            .field("progress_callback", &fp.progress_callback.map(|_| Callback))
+            .field(
+                "encoder_begin_callback",
+                &fp.encoder_begin_callback.map(|_| Callback),
+            )
+            .field("abort_callback", &fp.abort_callback.map(|_| Callback))
+            .field(
+                "logits_filter_callback",
+                &fp.logits_filter_callback.map(|_| Callback),
+            )
+            .field("n_grammar_rules", &fp.n_grammar_rules)
+            .field("i_start_rule", &fp.i_start_rule)
+            .field("grammar_penalty", &fp.grammar_penalty)
+            .field("vad", &fp.vad)
+            .field("vad_model_path", &string(fp.vad_model_path))
+            .finish_non_exhaustive()
+    }
+}
+
+/// A consuming builder for [`FullParams`], to configure them without a mutable variable.
+///
+/// Every method forwards to the matching `set_*` method of [`FullParams`],
+/// so see those for details and defaults. Strings are copied into the built [`FullParams`].
+///
+/// # Examples
+/// ```
+/// # use whisper_rs::{FullParams, SamplingStrategy};
+/// let params = FullParams::builder(SamplingStrategy::Greedy { best_of: 1 })
+///     .language("en")
+///     .translate(true)
+///     .n_threads(4)
+///     .print_progress(false)
+///     .build();
+/// ```
+pub struct FullParamsBuilder<'a, 'b> {
+    params: FullParams<'a, 'b>,
+}
+
+macro_rules! builder_setters {
+    ($($name:ident => $setter:ident($ty:ty);)*) => {
+        $(
+            #[doc = concat!("See [`FullParams::", stringify!($setter), "`].")]
+            pub fn $name(mut self, value: $ty) -> Self {
+                self.params.$setter(value);
+                self
+            }
+        )*
+    };
+}
+
+impl<'a, 'b> FullParamsBuilder<'a, 'b> {
+    /// Start building parameters with the given sampling strategy, see [`FullParams::new`].
+    pub fn new(sampling_strategy: SamplingStrategy) -> Self {
+        Self {
+            params: FullParams::new(sampling_strategy),
+        }
+    }
+
+    /// See [`FullParams::set_language`]. Use [`Self::auto_detect_language`] for auto-detection.
+    pub fn language(mut self, language: &'a str) -> Self {
+        self.params.set_language(Some(language));
+        self
+    }
+
+    /// Detect the language instead of setting it, see [`FullParams::set_language`].
+    pub fn auto_detect_language(mut self) -> Self {
+        self.params.set_language(None);
+        self
+    }
+
+    /// See [`FullParams::set_language_typed`].
+    pub fn language_typed(mut self, language: Language) -> Self {
+        self.params.set_language_typed(Some(language));
+        self
+    }
+
+    /// See [`FullParams::set_initial_prompt`].
+    pub fn initial_prompt(mut self, initial_prompt: &str) -> Self {
+        self.params.set_initial_prompt(initial_prompt);
+        self
+    }
+
+    /// See [`FullParams::set_tokens`].
+    pub fn tokens(mut self, tokens: &'b [c_int]) -> Self {
+        self.params.set_tokens(tokens);
+        self
+    }
+
+    /// See [`FullParams::set_prompt_tokens`].
+    pub fn prompt_tokens(mut self, tokens: &[WhisperTokenId]) -> Self {
+        self.params.set_prompt_tokens(tokens);
+        self
+    }
+
+    builder_setters! {
+        n_threads => set_n_threads(c_int);
+        n_max_text_ctx => set_n_max_text_ctx(c_int);
+        offset_ms => set_offset_ms(c_int);
+        duration_ms => set_duration_ms(c_int);
+        translate => set_translate(bool);
+        no_context => set_no_context(bool);
+        no_timestamps => set_no_timestamps(bool);
+        single_segment => set_single_segment(bool);
+        print_special => set_print_special(bool);
+        print_progress => set_print_progress(bool);
+        print_realtime => set_print_realtime(bool);
+        print_timestamps => set_print_timestamps(bool);
+        token_timestamps => set_token_timestamps(bool);
+        thold_pt => set_thold_pt(f32);
+        thold_ptsum => set_thold_ptsum(f32);
+        max_len => set_max_len(c_int);
+        split_on_word => set_split_on_word(bool);
+        max_tokens => set_max_tokens(c_int);
+        audio_ctx => set_audio_ctx(c_int);
+        tdrz_enable => set_tdrz_enable(bool);
+        detect_language => set_detect_language(bool);
+        suppress_blank => set_suppress_blank(bool);
+        suppress_nst => set_suppress_nst(bool);
+        suppress_regex => set_suppress_regex(Option<&str>);
+        temperature => set_temperature(f32);
+        max_initial_ts => set_max_initial_ts(f32);
+        length_penalty => set_length_penalty(f32);
+        temperature_inc => set_temperature_inc(f32);
+        entropy_thold => set_entropy_thold(f32);
+        logprob_thold => set_logprob_thold(f32);
+        no_speech_thold => set_no_speech_thold(f32);
+        grammar_penalty => set_grammar_penalty(f32);
+        vad_model_path => set_vad_model_path(Option<&str>);
+        enable_vad => enable_vad(bool);
+        vad_params => set_vad_params(WhisperVadParams);
+    }
+
+    /// Finish building the parameters.
+    pub fn build(self) -> FullParams<'a, 'b> {
+        self.params
+    }
+}
+
 // following implementations are safe
 // see https://github.com/ggerganov/whisper.cpp/issues/32#issuecomment-1272790388
 // concurrent usage is prevented by &mut self on methods that modify the struct
@@ -927,3 +1691,119 @@ mod test_whisper_params_initial_prompt {
         );
     }
 }
+
+#[cfg(test)]
+mod test_whisper_params_vad_model_path {
+    use super::*;
+
+    #[test]
+    fn test_vad_model_path_outlives_clone() {
+        let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+        params.set_vad_model_path(Some("models/silero.bin"));
+        params.enable_vad(true);
+        let cloned = params.clone();
+        drop(params);
+
+        let path = unsafe { std::ffi::CStr::from_ptr(cloned.fp.vad_model_path) };
+        assert_eq!(path.to_str().unwrap(), "models/silero.bin");
+        assert!(cloned.fp.vad);
+    }
+
+    #[test]
+    fn test_clear_vad_model_path_disables_vad() {
+        let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+        params.set_vad_model_path(Some("models/silero.bin"));
+        params.enable_vad(true);
+        params.set_vad_model_path(None);
+
+        assert!(params.fp.vad_model_path.is_null());
+        assert!(!params.fp.vad);
+    }
+}
+
+#[cfg(test)]
+mod test_whisper_params_debug {
+    use super::*;
+
+    #[test]
+    fn test_debug_output() {
+        let mut params = FullParams::new(SamplingStrategy::BeamSearch {
+            beam_size: 3,
+            patience: -1.0,
+        });
+        params.set_language(Some("de"));
+        params.set_progress_callback_safe(|_| {});
+
+        let debug = format!("{:?}", params);
+        assert!(debug.contains("strategy: BeamSearch { beam_size: 3, patience: -1.0 }"));
+        assert!(debug.contains("language: Some(\"de\")"));
+        assert!(debug.contains("initial_prompt: None"));
+        assert!(debug.contains("progress_callback: Some(<callback>)"));
+    }
+}
+
+#[cfg(test)]
+mod test_whisper_params_progress {
+    use super::*;
+
+    #[test]
+    fn test_progress_eta() {
+        let progress = Progress::new(25, Duration::from_secs(10));
+        assert_eq!(progress.eta, Some(Duration::from_secs(30)));
+        assert_eq!(
+            Progress::new(100, Duration::from_secs(10)).eta,
+            Some(Duration::ZERO)
+        );
+        assert_eq!(Progress::new(0, Duration::from_secs(10)).eta, None);
+    }
+}
+
+#[cfg(test)]
+mod test_whisper_params_callback_panic {
+    use super::*;
+    use std::ptr::null_mut;
+
+    #[test]
+    fn test_panicking_callback_is_caught() {
+        let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+        params.set_progress_callback_safe(|_| panic!("progress"));
+        params.set_abort_callback_safe(|| false);
+
+        // call the trampolines the way whisper.cpp would
+        unsafe {
+            let progress = params.fp.progress_callback.unwrap();
+            progress(
+                null_mut(),
+                null_mut(),
+                10,
+                params.fp.progress_callback_user_data,
+            );
+            let abort = params.fp.abort_callback.unwrap();
+            assert!(abort(params.fp.abort_callback_user_data));
+        }
+
+        let payload = params
+            .callback_panic
+            .take()
+            .expect("panic should be caught");
+        assert_eq!(payload.downcast_ref::<&str>(), Some(&"progress"));
+        assert!(params.callback_panic.take().is_none());
+    }
+
+    #[test]
+    fn test_callbacks_run_without_panic() {
+        let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+        params.set_encoder_begin_callback_safe(|| true);
+
+        let encoder_begin = params.fp.encoder_begin_callback.unwrap();
+        let ret = unsafe {
+            encoder_begin(
+                null_mut(),
+                null_mut(),
+                params.fp.encoder_begin_callback_user_data,
+            )
+        };
+        assert!(ret);
+        assert!(params.callback_panic.take().is_none());
+    }
+}
@@ -1,21 +1,55 @@
 use std::ffi::c_int;
+use std::ops::Range;
 use std::sync::Arc;
+use std::time::Duration;
 
 use crate::{FullParams, WhisperError, WhisperInnerContext, WhisperTokenId};
 
 mod iterator;
 mod segment;
+mod segment_data;
 mod token;
+mod transcript;
+mod word;
 
 pub use iterator::WhisperStateSegmentIterator;
 pub use segment::WhisperSegment;
+pub use segment_data::{WhisperSegmentData, WhisperTokenInfo};
 pub use token::WhisperToken;
+pub use transcript::{Chapter, Transcript};
+pub use word::Word;
+
+// magnitude at which a sample is considered clipped, and the fraction of clipped samples
+// above which `full` will log a warning
+#[cfg(any(feature = "log_backend", feature = "tracing_backend"))]
+const CLIPPING_THRESHOLD: f32 = 0.999;
+#[cfg(any(feature = "log_backend", feature = "tracing_backend"))]
+const CLIPPING_WARN_RATIO: f32 = 0.01;
 
 /// Rustified pointer to a Whisper state.
+///
+/// A state can transcribe any number of unrelated files one after the other,
+/// which avoids allocating a new state per file. See [`Self::reset`].
 #[derive(Debug)]
 pub struct WhisperState {
     ctx: Arc<WhisperInnerContext>,
     ptr: *mut whisper_rs_sys::whisper_state,
+    last_sample_range: Range<usize>,
+    // set by `reset`: the next `full` must not use text from before the reset as context
+    reset_context: bool,
+    // reused by `full_i16` and `pcm_to_mel_i16`, so converting audio doesn't allocate on every call.
+    // Cleared, but never shrunk, between calls
+    float_scratch: ScratchBuffer,
+}
+
+#[derive(Default)]
+struct ScratchBuffer(Vec<f32>);
+
+impl std::fmt::Debug for ScratchBuffer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // the contents are leftovers from the last conversion, and can be huge
+        write!(f, "ScratchBuffer {{ capacity: {} }}", self.0.capacity())
+    }
 }
 
 unsafe impl Send for WhisperState {}
@@ -38,7 +72,27 @@ impl WhisperState {
         ctx: Arc<WhisperInnerContext>,
         ptr: *mut whisper_rs_sys::whisper_state,
     ) -> Self {
-        Self { ctx, ptr }
+        Self {
+            ctx,
+            ptr,
+            last_sample_range: 0..0,
+            reset_context: false,
+            float_scratch: ScratchBuffer::default(),
+        }
+    }
+
+    /// Make the next call to [`Self::full`] start fresh, as if this state was newly created,
+    /// so it can transcribe a new, unrelated file.
+    ///
+    /// whisper.cpp has no way to clear a state directly. The only thing that carries over between
+    /// calls to [`Self::full`] is the text of the previous call, used as context for the decoder,
+    /// so the next call runs with [`crate::FullParams::set_no_context`] forced on.
+    /// Prompt tokens and the initial prompt set on its params are still used.
+    ///
+    /// The segments of the previous call stay readable until the next call to [`Self::full`].
+    pub fn reset(&mut self) {
+        self.reset_context = true;
+        self.last_sample_range = 0..0;
     }
 
     /// Convert raw PCM audio (floating point 32 bit) to log mel spectrogram.
@@ -53,6 +107,10 @@ impl WhisperState {
     ///
     /// # C++ equivalent
     /// `int whisper_pcm_to_mel(struct whisper_context * ctx, const float * samples, int n_samples, int n_threads)`
+    #[cfg_attr(
+        feature = "tracing_backend",
+        tracing::instrument(level = "debug", skip_all, fields(n_samples = pcm.len(), threads))
+    )]
     pub fn pcm_to_mel(&mut self, pcm: &[f32], threads: usize) -> Result<(), WhisperError> {
         if threads < 1 {
             return Err(WhisperError::InvalidThreadCount);
@@ -75,6 +133,20 @@ impl WhisperState {
         }
     }
 
+    /// The same as [`Self::pcm_to_mel`], but takes 16 bit integer PCM audio.
+    ///
+    /// Like [`Self::full_i16`], the audio is converted into a buffer kept by this state,
+    /// which is reused across calls.
+    ///
+    /// # Returns
+    /// Ok(()) on success, Err(WhisperError) on failure.
+    pub fn pcm_to_mel_i16(&mut self, pcm: &[i16], threads: usize) -> Result<(), WhisperError> {
+        let samples = self.convert_to_scratch(pcm)?;
+        let ret = self.pcm_to_mel(&samples, threads);
+        self.float_scratch.0 = samples;
+        ret
+    }
+
     /// This can be used to set a custom log mel spectrogram inside the provided whisper state.
     /// Use this instead of whisper_pcm_to_mel() if you want to provide your own log mel spectrogram.
     ///
@@ -124,6 +196,10 @@ impl WhisperState {
     ///
     /// # C++ equivalent
     /// `int whisper_encode(struct whisper_context * ctx, int offset, int n_threads)`
+    #[cfg_attr(
+        feature = "tracing_backend",
+        tracing::instrument(level = "debug", skip(self))
+    )]
     pub fn encode(&mut self, offset: usize, threads: usize) -> Result<(), WhisperError> {
         if threads < 1 {
             return Err(WhisperError::InvalidThreadCount);
@@ -160,6 +236,10 @@ impl WhisperState {
     ///
     /// # C++ equivalent
     /// `int whisper_decode(struct whisper_context * ctx, const whisper_token * tokens, int n_tokens, int n_past, int n_threads)`
+    #[cfg_attr(
+        feature = "tracing_backend",
+        tracing::instrument(level = "debug", skip_all, fields(n_tokens = tokens.len(), n_past, threads))
+    )]
     pub fn decode(
         &mut self,
         tokens: &[WhisperTokenId],
@@ -199,6 +279,8 @@ impl WhisperState {
     /// # Returns
     /// `Ok((i32, Vec<f32>))` on success where the i32 is detected language id and Vec<f32>
     /// is array with the probabilities of all languages, `Err(WhisperError)` on failure.
+    /// Use [`crate::get_lang_str`] or [`crate::get_lang_str_full`] to get the code or name of a language id,
+    /// and [`crate::LanguageProbabilities`] to rank the probabilities.
     ///
     /// # C++ equivalent
     /// `int whisper_lang_auto_detect(struct whisper_context * ctx, int offset_ms, int n_threads, float * lang_probs)`
@@ -228,9 +310,32 @@ impl WhisperState {
         }
     }
 
+    /// The same as [`Self::lang_detect`], but returns the code of the detected language
+    /// (such as "en" or "de") rather than its id, along with its probability.
+    ///
+    /// # Returns
+    /// `Ok((String, f32, Vec<f32>))` on success, where the String is the code of the detected language,
+    /// the f32 is its probability and the Vec<f32> is the probabilities of all languages, indexed by language id.
+    /// `Err(WhisperError)` on failure.
+    pub fn lang_detect_str(
+        &self,
+        offset_ms: usize,
+        threads: usize,
+    ) -> Result<(String, f32, Vec<f32>), WhisperError> {
+        let (lang_id, lang_probs) = self.lang_detect(offset_ms, threads)?;
+        let lang = crate::standalone::get_lang_str(lang_id).ok_or(WhisperError::NullPointer)?;
+        let probability = lang_probs[lang_id as usize];
+        Ok((lang.to_string(), probability, lang_probs))
+    }
+
     // logit functions
     /// Gets logits obtained from the last call to [WhisperState::decode].
-    /// As of whisper.cpp 1.4.1, only a single row of logits is available, corresponding to the last token in the input.
+    /// Only a single row of logits is available, corresponding to the last token in the input:
+    /// `whisper_decode` only asks the decoder to compute logits for the last position of its batch,
+    /// so rows for the other positions never exist.
+    ///
+    /// To get the logits at every position of a token sequence, decode it one token at a time,
+    /// passing the number of tokens decoded so far as `n_past`, and read the logits after each call.
     ///
     /// # Returns
     /// A slice of logits with length equal to n_vocab.
@@ -279,6 +384,19 @@ impl WhisperState {
     /// * pcm: raw PCM audio data, 32 bit floating point at a sample rate of 16 kHz, 1 channel.
     ///   See utilities in the root of this crate for functions to convert audio to this format.
     ///
+    /// If [`crate::FullParams::set_detect_language`] is enabled, this stops after detecting the language,
+    /// which can be read with [`Self::full_lang_id_from_state`], and produces no segments.
+    ///
+    /// If the `log_backend` or `tracing_backend` feature is enabled, a warning is logged
+    /// when more than 1% of the input is clipped (see [`crate::clipping_ratio`]).
+    /// If the `tracing_backend` feature is enabled, this runs in a debug span,
+    /// and emits a debug event for each transcribed segment.
+    ///
+    /// # Panics
+    /// If a callback set with one of the `_safe` setters of [`FullParams`] panics, the panic is caught
+    /// before it reaches whisper.cpp, the remaining callbacks are skipped, and it's resumed here
+    /// once whisper.cpp has returned.
+    ///
     /// # Returns
     /// Ok(()) on success, Err(WhisperError) on failure.
     ///
@@ -289,31 +407,183 @@ impl WhisperState {
     ///             struct whisper_full_params   params,
     ///                            const float * samples,
     ///                                    int   n_samples)`
+    #[cfg_attr(
+        feature = "tracing_backend",
+        tracing::instrument(
+            level = "debug",
+            skip_all,
+            fields(n_samples = data.len(), threads = params.fp.n_threads)
+        )
+    )]
     pub fn full(&mut self, params: FullParams, data: &[f32]) -> Result<(), WhisperError> {
         if data.is_empty() {
             // can randomly trigger segmentation faults if we don't check this
             return Err(WhisperError::NoSamples);
         }
 
+        #[cfg(any(feature = "log_backend", feature = "tracing_backend"))]
+        {
+            let clipping = crate::clipping_ratio(data, CLIPPING_THRESHOLD);
+            if clipping > CLIPPING_WARN_RATIO {
+                crate::common_logging::generic_warn!(
+                    "{:.1}% of input samples are clipped, transcription quality may suffer",
+                    clipping * 100.0
+                );
+            }
+        }
+
+        let sample_range = Self::processed_sample_range(&params, data.len());
+        params.start_progress_timer();
+        let mut fp = params.fp;
+        if self.reset_context {
+            fp.no_context = true;
+        }
         let ret = unsafe {
             whisper_rs_sys::whisper_full_with_state(
                 self.ctx.ctx,
                 self.ptr,
-                params.fp,
+                fp,
                 data.as_ptr(),
                 data.len() as c_int,
             )
         };
-        if ret == -1 {
-            Err(WhisperError::UnableToCalculateSpectrogram)
-        } else if ret == 7 {
-            Err(WhisperError::FailedToEncode)
-        } else if ret == 8 {
-            Err(WhisperError::FailedToDecode)
-        } else if ret == 0 {
-            Ok(())
+        // the safe callbacks catch their panics rather than unwinding through whisper.cpp,
+        // rethrow the first one now that we're back on the Rust side
+        if let Some(payload) = params.callback_panic.take() {
+            std::panic::resume_unwind(payload);
+        }
+        if ret == 0 {
+            self.last_sample_range = sample_range;
+            self.reset_context = false;
+
+            #[cfg(feature = "tracing_backend")]
+            for segment in self.as_iter() {
+                tracing::debug!(
+                    segment = segment.segment_index(),
+                    start_timestamp = segment.start_timestamp(),
+                    end_timestamp = segment.end_timestamp(),
+                    n_tokens = segment.n_tokens(),
+                    "transcribed segment"
+                );
+            }
+        }
+        full_result(ret)
+    }
+
+    /// Run [`Self::full`] and copy the resulting segments out with [`Self::collect_segments`].
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
+    /// # let audio = vec![0.0f32; 16000];
+    /// let ctx = WhisperContext::new_with_params("model.bin", WhisperContextParameters::default())?;
+    /// let mut state = ctx.create_state()?;
+    /// let params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+    /// for segment in state.transcribe(params, &audio)? {
+    ///     println!("[{} - {}]: {}", segment.start_timestamp, segment.end_timestamp, segment.text);
+    /// }
+    /// # Ok::<(), whisper_rs::WhisperError>(())
+    /// ```
+    ///
+    /// # Returns
+    /// Ok(Vec<WhisperSegmentData>) on success, Err(WhisperError) on failure.
+    pub fn transcribe(
+        &mut self,
+        params: FullParams,
+        data: &[f32],
+    ) -> Result<Vec<WhisperSegmentData>, WhisperError> {
+        self.full(params, data)?;
+        self.collect_segments()
+    }
+
+    /// The same as [`Self::full`], but takes 16 bit integer PCM audio (16 kHz, 1 channel).
+    ///
+    /// The audio is converted with [`crate::convert_integer_to_float_audio`] into a buffer
+    /// kept by this state (and shared with [`Self::pcm_to_mel_i16`]), so repeated calls
+    /// don't allocate once the buffer has grown to the size of the largest input.
+    ///
+    /// # Returns
+    /// Ok(()) on success, Err(WhisperError) on failure.
+    pub fn full_i16(&mut self, params: FullParams, data: &[i16]) -> Result<(), WhisperError> {
+        let samples = self.convert_to_scratch(data)?;
+        let ret = self.full(params, &samples);
+        self.float_scratch.0 = samples;
+        ret
+    }
+
+    /// Convert `data` into the scratch buffer, which is taken out of `self` so it can be
+    /// passed to methods taking `&mut self`. Put it back once done.
+    fn convert_to_scratch(&mut self, data: &[i16]) -> Result<Vec<f32>, WhisperError> {
+        let mut samples = std::mem::take(&mut self.float_scratch.0);
+        samples.clear();
+        samples.resize(data.len(), 0.0);
+        crate::convert_integer_to_float_audio(data, &mut samples)?;
+        Ok(samples)
+    }
+
+    /// The range of samples, as indices into the buffer passed to the last successful call to
+    /// [`Self::full`], that whisper.cpp actually transcribed.
+    ///
+    /// This reflects the window selected by [`FullParams::set_offset_ms`] and
+    /// [`FullParams::set_duration_ms`], clamped to the length of the buffer.
+    /// Chunked pipelines can use the end of this range to advance their read position.
+    ///
+    /// Returns an empty range if [`Self::full`] has not succeeded yet.
+    pub fn last_processed_sample_range(&self) -> Range<usize> {
+        self.last_sample_range.clone()
+    }
+
+    pub(crate) fn processed_sample_range(params: &FullParams, n_samples: usize) -> Range<usize> {
+        let samples_per_ms = (whisper_rs_sys::WHISPER_SAMPLE_RATE / 1000) as usize;
+        let start = (params.fp.offset_ms.max(0) as usize * samples_per_ms).min(n_samples);
+        let end = if params.fp.duration_ms > 0 {
+            (start + params.fp.duration_ms as usize * samples_per_ms).min(n_samples)
         } else {
-            Err(WhisperError::GenericError(ret))
+            n_samples
+        };
+        start..end
+    }
+
+    /// Run [`Self::full`], retrying at an escalating temperature if the model fails to decode.
+    ///
+    /// Only [`WhisperError::FailedToDecode`] is retried: encoding doesn't depend on the temperature,
+    /// so any other error is returned straight away.
+    ///
+    /// Each retry runs on a fresh clone of `params` with its temperature raised by the
+    /// configured `temperature_inc` (or 0.2 if that is not positive), mirroring the
+    /// fallback whisper.cpp applies internally, but at the level of the whole run.
+    /// Since every attempt consumes a clone, `params` must be safe to reuse across runs.
+    ///
+    /// # Arguments
+    /// * params: [crate::FullParams] struct. Cloned for every attempt.
+    /// * data: raw PCM audio data, see [`Self::full`].
+    /// * max_retries: How many extra attempts to make after the first one fails.
+    ///
+    /// # Returns
+    /// Ok(usize) with the number of retries that were needed (0 if the first attempt succeeded),
+    /// Err(WhisperError) if a non-retryable error occurred or every attempt failed.
+    pub fn full_with_retry(
+        &mut self,
+        params: FullParams,
+        data: &[f32],
+        max_retries: usize,
+    ) -> Result<usize, WhisperError> {
+        let base_temperature = params.fp.temperature;
+        let temperature_inc = if params.fp.temperature_inc > 0.0 {
+            params.fp.temperature_inc
+        } else {
+            0.2
+        };
+
+        let mut retry = 0;
+        loop {
+            let mut attempt = params.clone();
+            attempt.set_temperature(base_temperature + temperature_inc * retry as f32);
+            match self.full(attempt, data) {
+                Ok(()) => return Ok(retry),
+                Err(WhisperError::FailedToDecode) if retry < max_retries => retry += 1,
+                Err(e) => return Err(e),
+            }
         }
     }
 
@@ -328,6 +598,10 @@ impl WhisperState {
 
     /// Language ID associated with the provided state.
     ///
+    /// After [`Self::full`], this is the language that was transcribed (or detected, if the language
+    /// was "auto" or [`crate::FullParams::set_detect_language`] was enabled).
+    /// Use [`crate::get_lang_str`] to get its code, which [`crate::Language::from_code`] accepts.
+    ///
     /// # C++ equivalent
     /// `int whisper_full_lang_id_from_state(struct whisper_state * state);`
     pub fn full_lang_id_from_state(&self) -> c_int {
@@ -359,4 +633,213 @@ impl WhisperState {
     pub fn as_iter(&self) -> WhisperStateSegmentIterator<'_> {
         WhisperStateSegmentIterator::new(self)
     }
+
+    /// Get an iterator over every token of every segment, in transcript order.
+    ///
+    /// This is the same as calling [`WhisperSegment::tokens`] on each segment of [`Self::as_iter`],
+    /// and includes special tokens, such as timestamp tokens.
+    pub fn tokens(&self) -> impl Iterator<Item = WhisperToken<'_, '_>> + '_ {
+        self.as_iter().flat_map(|segment| {
+            // SAFETY: every index in 0..n_tokens is in bounds for the segment
+            (0..segment.n_tokens()).map(move |token| unsafe {
+                WhisperToken::from_segment_unchecked(segment.clone(), token)
+            })
+        })
+    }
+
+    /// Get a single no-speech probability for the whole transcribed clip.
+    ///
+    /// This is the mean of [`WhisperSegment::no_speech_probability`] over all segments,
+    /// weighted by the duration of each segment, so a long silent segment isn't outweighed
+    /// by several short ones. If every segment has zero duration, each segment is weighted equally.
+    ///
+    /// Combined with a small [`crate::FullParams::set_audio_ctx`], this can be used to cheaply
+    /// discard silent chunks of audio before transcribing them properly.
+    ///
+    /// # Returns
+    /// The probability, from 0.0 to 1.0. Returns 1.0 if there are no segments,
+    /// as whisper.cpp didn't find any speech to transcribe.
+    pub fn overall_no_speech_probability(&self) -> f32 {
+        let (mut weighted_sum, mut total_duration) = (0.0f64, 0.0f64);
+        let (mut sum, mut count) = (0.0f64, 0usize);
+        for segment in self.as_iter() {
+            let probability = segment.no_speech_probability() as f64;
+            let duration = (segment.end_timestamp() - segment.start_timestamp()).max(0) as f64;
+            weighted_sum += probability * duration;
+            total_duration += duration;
+            sum += probability;
+            count += 1;
+        }
+
+        if count == 0 {
+            1.0
+        } else if total_duration > 0.0 {
+            (weighted_sum / total_duration) as f32
+        } else {
+            (sum / count as f64) as f32
+        }
+    }
+
+    /// How much audio the current transcript covers: the end timestamp of the last segment.
+    ///
+    /// # Returns
+    /// The duration from the start of the audio, or [`Duration::ZERO`] if there are no segments.
+    pub fn transcript_duration(&self) -> Duration {
+        self.get_segment(self.full_n_segments() - 1)
+            .map_or(Duration::ZERO, |s| {
+                centiseconds_to_duration(s.end_timestamp())
+            })
+    }
+
+    /// Whether the context this state belongs to computes DTW token timestamps.
+    ///
+    /// When this is `false`, [`crate::WhisperToken::token_data`]'s `t_dtw` is always `-1`,
+    /// and [`WhisperSegment::words`] has no usable timing.
+    /// DTW is enabled with [`crate::WhisperContextParameters::dtw_parameters`],
+    /// and is disabled if flash attention is enabled.
+    pub fn has_dtw_timestamps(&self) -> bool {
+        self.ctx.dtw_token_timestamps
+    }
+
+    /// Get the text of every segment, concatenated.
+    ///
+    /// Invalid UTF-8 is replaced with the replacement character,
+    /// and leading whitespace (whisper.cpp usually starts segments with a space) is trimmed.
+    ///
+    /// # Returns
+    /// * On success: the text of the whole transcript.
+    /// * On failure: [`WhisperError::NullPointer`]
+    pub fn full_text(&self) -> Result<String, WhisperError> {
+        let mut text = String::new();
+        for segment in self.as_iter() {
+            text.push_str(&segment.to_str_lossy()?);
+        }
+        Ok(text.trim_start().to_string())
+    }
+
+    /// Get the raw bytes of every segment, concatenated.
+    ///
+    /// Unlike [`Self::full_text`], this does no UTF-8 validation or trimming.
+    ///
+    /// # Returns
+    /// * On success: the raw bytes of the whole transcript.
+    /// * On failure: [`WhisperError::NullPointer`]
+    pub fn full_bytes(&self) -> Result<Vec<u8>, WhisperError> {
+        let mut bytes = Vec::new();
+        for segment in self.as_iter() {
+            bytes.extend_from_slice(segment.to_bytes()?);
+        }
+        Ok(bytes)
+    }
+
+    /// Get an iterator over owned copies of every segment, see [`WhisperSegment::to_owned`].
+    ///
+    /// Unlike [`Self::as_iter`], the items don't borrow this state,
+    /// so they can be stored, returned from functions or sent to other threads.
+    /// To copy every segment at once, see [`Self::collect_segments`].
+    ///
+    /// This never fails: if whisper.cpp returns a null pointer for the text of a segment or token,
+    /// that text is left empty. Use [`Self::collect_segments`] to get an error instead.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use whisper_rs::{WhisperSegmentData, WhisperState};
+    /// fn segments(state: &WhisperState) -> Vec<WhisperSegmentData> {
+    ///     state.owned_segments().collect()
+    /// }
+    /// ```
+    pub fn owned_segments(&self) -> impl Iterator<Item = WhisperSegmentData> + '_ {
+        self.as_iter()
+            .map(|segment| WhisperSegmentData::from_segment_lossy(&segment))
+    }
+
+    /// Copy every segment, with its tokens, into an owned [`WhisperSegmentData`].
+    ///
+    /// # Returns
+    /// * On success: the segments, with any invalid UTF-8 replaced with the replacement character.
+    /// * On failure: [`WhisperError::NullPointer`]
+    pub fn collect_segments(&self) -> Result<Vec<WhisperSegmentData>, WhisperError> {
+        self.as_iter().map(|segment| segment.to_owned()).collect()
+    }
+
+    /// Copy every segment into an owned [`Transcript`].
+    ///
+    /// # Returns
+    /// * On success: the [`Transcript`], with any invalid UTF-8 replaced with the replacement character.
+    /// * On failure: [`WhisperError::NullPointer`]
+    pub fn transcript(&self) -> Result<Transcript, WhisperError> {
+        Transcript::from_state(self)
+    }
+}
+
+/// Convert a whisper.cpp timestamp in centiseconds to a [`Duration`], clamping negative ones to zero.
+pub(crate) fn centiseconds_to_duration(centiseconds: i64) -> Duration {
+    Duration::from_millis(centiseconds.max(0) as u64 * 10)
+}
+
+/// Map the return code of `whisper_full_with_state` to a [`WhisperError`].
+fn full_result(ret: c_int) -> Result<(), WhisperError> {
+    // whisper.cpp returns -6 if encoding fails, and -7 or -8 if decoding fails
+    // (7 and 8 are kept for compatibility with older versions of this crate)
+    match ret {
+        0 => Ok(()),
+        -1 | -2 => Err(WhisperError::UnableToCalculateSpectrogram),
+        -3 => Err(WhisperError::FailedToDetectLanguage),
+        -4 => Err(WhisperError::TooManyDecoders),
+        -5 => Err(WhisperError::AudioCtxTooLarge),
+        -6 | 7 => Err(WhisperError::FailedToEncode),
+        -7 | -8 | 8 => Err(WhisperError::FailedToDecode),
+        _ => Err(WhisperError::GenericError(ret)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_full_result() {
+        assert!(full_result(0).is_ok());
+        assert!(matches!(full_result(-6), Err(WhisperError::FailedToEncode)));
+        // `full_with_retry` relies on decode failures being reported as such
+        assert!(matches!(full_result(-7), Err(WhisperError::FailedToDecode)));
+        assert!(matches!(full_result(-8), Err(WhisperError::FailedToDecode)));
+        assert!(matches!(
+            full_result(-3),
+            Err(WhisperError::FailedToDetectLanguage)
+        ));
+        assert!(matches!(
+            full_result(-4),
+            Err(WhisperError::TooManyDecoders)
+        ));
+        assert!(matches!(
+            full_result(-5),
+            Err(WhisperError::AudioCtxTooLarge)
+        ));
+        assert!(matches!(
+            full_result(-9),
+            Err(WhisperError::GenericError(-9))
+        ));
+    }
+
+    #[test]
+    fn test_processed_sample_range() {
+        let range = |offset_ms, duration_ms, n_samples| {
+            let mut params = FullParams::new(crate::SamplingStrategy::Greedy { best_of: 1 });
+            params.set_offset_ms(offset_ms);
+            params.set_duration_ms(duration_ms);
+            WhisperState::processed_sample_range(&params, n_samples)
+        };
+
+        // 16 samples per millisecond
+        assert_eq!(range(0, 0, 32000), 0..32000);
+        assert_eq!(range(500, 0, 32000), 8000..32000);
+        assert_eq!(range(500, 1000, 32000), 8000..24000);
+        // past the end
+        assert_eq!(range(500, 5000, 32000), 8000..32000);
+        assert_eq!(range(5000, 1000, 32000), 32000..32000);
+        // negative values are treated as 0
+        assert_eq!(range(-500, 0, 32000), 0..32000);
+        assert_eq!(range(500, -1000, 32000), 8000..32000);
+    }
 }
@@ -1,9 +1,24 @@
-use crate::{WhisperError, WhisperState, WhisperToken};
+use super::centiseconds_to_duration;
+use crate::{WhisperError, WhisperSegmentData, WhisperState, WhisperToken, Word};
 use std::borrow::Cow;
 use std::ffi::{c_int, CStr};
 use std::fmt;
+use std::time::Duration;
 
 /// A segment returned by Whisper after running the transcription pipeline.
+///
+/// # Relation to the `whisper.cpp` API
+/// `whisper.cpp` has two sets of result getters: `whisper_full_get_*`, which read results stored
+/// in the context itself, and `whisper_full_get_*_from_state`, which read them from a separate `whisper_state`.
+/// `whisper-rs` always creates contexts with the `_no_state` initializers and runs every transcription
+/// on a [`WhisperState`], so the context never holds any results and only the `_from_state` getters are used.
+/// The "C++ equivalent" listed on each method names the context getter for ease of porting C code,
+/// but the `_from_state` variant is what's actually called.
+///
+/// Code ported from `whisper.cpp` that calls `whisper_full(ctx, ...)` followed by `whisper_full_get_*(ctx, ...)`
+/// translates to [`WhisperState::full`] followed by the methods here:
+/// create the state once with [`crate::WhisperContext::create_state`] and use it where the C code used `ctx`.
+#[derive(Clone)]
 pub struct WhisperSegment<'a> {
     state: &'a WhisperState,
 
@@ -62,6 +77,46 @@ impl<'a> WhisperSegment<'a> {
         }
     }
 
+    /// Get the start time of this segment in seconds.
+    ///
+    /// See [`Self::start_timestamp`].
+    pub fn start_seconds(&self) -> f64 {
+        self.start_timestamp() as f64 / 100.0
+    }
+
+    /// Get the end time of this segment in seconds.
+    ///
+    /// See [`Self::end_timestamp`].
+    pub fn end_seconds(&self) -> f64 {
+        self.end_timestamp() as f64 / 100.0
+    }
+
+    /// Get the length of this segment in seconds.
+    pub fn duration_seconds(&self) -> f64 {
+        (self.end_timestamp() - self.start_timestamp()) as f64 / 100.0
+    }
+
+    /// Get the start time of this segment as a [`Duration`] from the start of the audio.
+    ///
+    /// Negative timestamps are clamped to zero.
+    pub fn start_time(&self) -> Duration {
+        centiseconds_to_duration(self.start_timestamp())
+    }
+
+    /// Get the end time of this segment as a [`Duration`] from the start of the audio.
+    ///
+    /// Negative timestamps are clamped to zero.
+    pub fn end_time(&self) -> Duration {
+        centiseconds_to_duration(self.end_timestamp())
+    }
+
+    /// Get the length of this segment as a [`Duration`].
+    ///
+    /// Zero if the segment ends before it starts.
+    pub fn duration(&self) -> Duration {
+        centiseconds_to_duration(self.end_timestamp() - self.start_timestamp())
+    }
+
     /// Get number of tokens in this segment.
     ///
     /// # Returns
@@ -158,6 +213,46 @@ impl<'a> WhisperSegment<'a> {
         Ok(self.to_raw_cstr()?.to_string_lossy())
     }
 
+    /// Split the text of this segment into sentences.
+    ///
+    /// Invalid UTF-8 is replaced with the replacement character.
+    /// See [`crate::Transcript::sentences`] for the heuristic used.
+    ///
+    /// # Returns
+    /// * On success: the sentences, trimmed of surrounding whitespace.
+    /// * On failure: [`WhisperError::NullPointer`]
+    pub fn sentences(&self) -> Result<Vec<String>, WhisperError> {
+        Ok(super::transcript::split_sentences(&self.to_str_lossy()?))
+    }
+
+    /// Get the part of the original audio covered by this segment.
+    ///
+    /// # Arguments
+    /// * samples: The audio that was transcribed, starting at timestamp 0.
+    /// * sample_rate: The sample rate of `samples`, in Hz.
+    ///
+    /// # Returns
+    /// The samples between [`Self::start_timestamp`] and [`Self::end_timestamp`],
+    /// rounded to the nearest sample and clamped to the bounds of `samples`.
+    pub fn extract_audio<'s>(&self, samples: &'s [f32], sample_rate: u32) -> &'s [f32] {
+        &samples[crate::utilities::centiseconds_to_sample_range(
+            self.start_timestamp() as f64,
+            self.end_timestamp() as f64,
+            sample_rate,
+            samples.len(),
+        )]
+    }
+
+    /// Copy this segment, with its tokens, into an owned [`WhisperSegmentData`]
+    /// that doesn't borrow the [`WhisperState`].
+    ///
+    /// # Returns
+    /// * On success: the segment, with any invalid UTF-8 replaced with the replacement character.
+    /// * On failure: [`WhisperError::NullPointer`]
+    pub fn to_owned(&self) -> Result<WhisperSegmentData, WhisperError> {
+        WhisperSegmentData::from_segment(self)
+    }
+
     fn token_in_bounds(&self, token_idx: c_int) -> bool {
         token_idx >= 0 && token_idx < self.token_count
     }
@@ -169,6 +264,121 @@ impl<'a> WhisperSegment<'a> {
             .then(|| unsafe { WhisperToken::new_unchecked(self, token) })
     }
 
+    /// Get an iterator over all tokens in this segment, in order.
+    ///
+    /// This includes special tokens, such as timestamp tokens.
+    pub fn tokens(&self) -> impl ExactSizeIterator<Item = WhisperToken<'_, '_>> + '_ {
+        // SAFETY: every index in 0..self.token_count is in bounds
+        (0..self.token_count).map(move |token| unsafe { WhisperToken::new_unchecked(self, token) })
+    }
+
+    /// Get the mean [`WhisperToken::token_probability`] of the text tokens in this segment.
+    ///
+    /// Special tokens (such as timestamp tokens) are skipped, as their probabilities
+    /// say nothing about the transcribed text. A low value is a sign the segment may be hallucinated.
+    ///
+    /// # Returns
+    /// The mean probability, or `None` if this segment has no text tokens.
+    pub fn average_token_probability(&self) -> Option<f32> {
+        self.average_over_text_tokens(|token| token.token_probability())
+    }
+
+    /// Get the mean log probability (`plog` of [`WhisperToken::token_data`]) of the text tokens in this segment.
+    ///
+    /// This is similar to the `avg_logprob` used by the reference Whisper implementation to detect failed decodes,
+    /// where values below -1.0 are treated as unreliable. Special tokens are skipped.
+    ///
+    /// # Returns
+    /// The mean log probability, or `None` if this segment has no text tokens.
+    pub fn average_logprob(&self) -> Option<f32> {
+        self.average_over_text_tokens(|token| token.token_data().plog)
+    }
+
+    fn average_over_text_tokens(&self, value: impl Fn(&WhisperToken) -> f32) -> Option<f32> {
+        let token_eot = self.state.ctx.token_eot();
+        let (sum, count) = self
+            .tokens()
+            .filter(|token| token.token_id() < token_eot)
+            .fold((0.0, 0), |(sum, count), token| {
+                (sum + value(&token), count + 1)
+            });
+        (count > 0).then(|| sum / count as f32)
+    }
+
+    /// Group the tokens of this segment into words, with timestamps and a confidence score.
+    ///
+    /// A token whose text starts with a space begins a new word, any other token is appended
+    /// to the word before it. Special tokens (such as timestamp tokens) are skipped.
+    ///
+    /// Each word starts at the DTW timestamp of its first token and ends where the next word starts,
+    /// with the last word ending at [`Self::end_timestamp`].
+    /// If DTW is not enabled (see [`WhisperState::has_dtw_timestamps`]), the much coarser
+    /// `t0` token timestamp is used instead.
+    ///
+    /// The confidence of a word is the mean [`WhisperToken::token_probability`] of its tokens.
+    pub fn words(&self) -> Vec<Word> {
+        struct PartialWord {
+            bytes: Vec<u8>,
+            start_centiseconds: i64,
+            probability_sum: f32,
+            n_tokens: usize,
+        }
+
+        let token_eot = self.state.ctx.token_eot();
+        let has_dtw = self.state.has_dtw_timestamps();
+        let mut partial_words: Vec<PartialWord> = Vec::new();
+        for token in self.tokens() {
+            if token.token_id() >= token_eot {
+                continue;
+            }
+            let Ok(bytes) = token.to_bytes() else {
+                continue;
+            };
+            let probability = token.token_probability();
+
+            match partial_words.last_mut() {
+                Some(word) if !bytes.starts_with(b" ") => {
+                    word.bytes.extend_from_slice(bytes);
+                    word.probability_sum += probability;
+                    word.n_tokens += 1;
+                }
+                _ => partial_words.push(PartialWord {
+                    bytes: bytes.to_vec(),
+                    start_centiseconds: {
+                        let data = token.token_data();
+                        if has_dtw {
+                            data.t_dtw
+                        } else {
+                            data.t0
+                        }
+                    },
+                    probability_sum: probability,
+                    n_tokens: 1,
+                }),
+            }
+        }
+
+        let end_centiseconds = partial_words
+            .iter()
+            .skip(1)
+            .map(|w| w.start_centiseconds)
+            .chain(std::iter::once(self.end_timestamp()))
+            .collect::<Vec<_>>();
+        partial_words
+            .into_iter()
+            .zip(end_centiseconds)
+            .map(|(word, end_centiseconds)| Word {
+                // decode once all bytes are joined, as tokens may split multibyte characters
+                text: String::from_utf8_lossy(&word.bytes)
+                    .trim_start()
+                    .to_string(),
+                start_centiseconds: word.start_centiseconds,
+                end_centiseconds,
+                confidence: word.probability_sum / word.n_tokens as f32,
+            })
+            .collect()
+    }
+
     /// The same as [`Self::get_token`] but without any bounds check.
     ///
     /// # Safety
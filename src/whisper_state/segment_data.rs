@@ -0,0 +1,207 @@
+use crate::{WhisperError, WhisperSegment, WhisperTokenId};
+use std::ffi::{c_char, c_int, CStr};
+
+/// An owned snapshot of a [`WhisperSegment`].
+///
+/// Unlike [`WhisperSegment`], this does not borrow the [`crate::WhisperState`] it came from,
+/// so it can be stored, moved across threads, and kept after the state is reused.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WhisperSegmentData {
+    /// The index of this segment in the state it came from.
+    pub segment: c_int,
+    /// Start time in centiseconds (10s of milliseconds).
+    pub start_timestamp: i64,
+    /// End time in centiseconds (10s of milliseconds).
+    pub end_timestamp: i64,
+    /// The text of this segment, with any invalid UTF-8 replaced with the replacement character.
+    pub text: String,
+    /// The no_speech probability of this segment.
+    pub no_speech_probability: f32,
+    /// Whether the next segment is predicted as a speaker turn.
+    pub next_segment_speaker_turn: bool,
+    /// The tokens of this segment, including special tokens.
+    pub tokens: Vec<WhisperTokenInfo>,
+}
+
+/// An owned snapshot of a [`crate::WhisperToken`], see [`WhisperSegmentData::tokens`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WhisperTokenInfo {
+    /// The token ID.
+    pub id: WhisperTokenId,
+    /// The text of this token, with any invalid UTF-8 replaced with the replacement character.
+    ///
+    /// Tokens may split multibyte characters, so the text of a single token may contain
+    /// replacement characters even when the text of the segment doesn't.
+    pub text: String,
+    /// The probability of this token.
+    pub probability: f32,
+    /// The log probability of this token.
+    pub log_probability: f32,
+    /// Start time in centiseconds, if token timestamps are enabled.
+    pub start_timestamp: i64,
+    /// End time in centiseconds, if token timestamps are enabled.
+    pub end_timestamp: i64,
+    /// DTW timestamp in centiseconds, or -1 if DTW is disabled.
+    pub dtw_timestamp: i64,
+}
+
+impl WhisperSegmentData {
+    pub(crate) fn from_segment(segment: &WhisperSegment<'_>) -> Result<Self, WhisperError> {
+        let state = segment.get_state();
+        // SAFETY: the segment index of a `WhisperSegment` is always in bounds for its state
+        unsafe { Self::from_raw(state.ctx.ctx, state.ptr, segment.segment_index()) }
+    }
+
+    /// Like [`Self::from_segment`], but text whisper.cpp returned a null pointer for is left empty.
+    pub(crate) fn from_segment_lossy(segment: &WhisperSegment<'_>) -> Self {
+        let state = segment.get_state();
+        // SAFETY: as in `from_segment`
+        let data = unsafe { Self::read(state.ctx.ctx, state.ptr, segment.segment_index(), true) };
+        match data {
+            Ok(data) => data,
+            Err(_) => unreachable!("lossy reads never fail"),
+        }
+    }
+
+    /// # Safety
+    /// * `ctx` and `state` must be valid pointers, with `state` created from `ctx`
+    /// * `segment` must be in bounds for `state`
+    pub(crate) unsafe fn from_raw(
+        ctx: *mut whisper_rs_sys::whisper_context,
+        state: *mut whisper_rs_sys::whisper_state,
+        segment: c_int,
+    ) -> Result<Self, WhisperError> {
+        Self::read(ctx, state, segment, false)
+    }
+
+    /// # Safety
+    /// See [`Self::from_raw`]. If `lossy` is set, this never fails.
+    unsafe fn read(
+        ctx: *mut whisper_rs_sys::whisper_context,
+        state: *mut whisper_rs_sys::whisper_state,
+        segment: c_int,
+        lossy: bool,
+    ) -> Result<Self, WhisperError> {
+        let text = read_text(
+            whisper_rs_sys::whisper_full_get_segment_text_from_state(state, segment),
+            lossy,
+        )?;
+
+        let n_tokens = whisper_rs_sys::whisper_full_n_tokens_from_state(state, segment);
+        let mut tokens = Vec::with_capacity(n_tokens.max(0) as usize);
+        for token in 0..n_tokens {
+            let data =
+                whisper_rs_sys::whisper_full_get_token_data_from_state(state, segment, token);
+            let text = read_text(
+                whisper_rs_sys::whisper_full_get_token_text_from_state(ctx, state, segment, token),
+                lossy,
+            )?;
+            tokens.push(WhisperTokenInfo {
+                id: data.id,
+                text,
+                probability: data.p,
+                log_probability: data.plog,
+                start_timestamp: data.t0,
+                end_timestamp: data.t1,
+                dtw_timestamp: data.t_dtw,
+            });
+        }
+
+        Ok(Self {
+            segment,
+            start_timestamp: whisper_rs_sys::whisper_full_get_segment_t0_from_state(state, segment),
+            end_timestamp: whisper_rs_sys::whisper_full_get_segment_t1_from_state(state, segment),
+            text,
+            no_speech_probability:
+                whisper_rs_sys::whisper_full_get_segment_no_speech_prob_from_state(state, segment),
+            next_segment_speaker_turn:
+                whisper_rs_sys::whisper_full_get_segment_speaker_turn_next_from_state(
+                    state, segment,
+                ),
+            tokens,
+        })
+    }
+
+    /// Move this segment and its tokens by `offset` centiseconds, e.g. when it was transcribed
+    /// from a slice of a longer recording. Negative token timestamps mean "not computed"
+    /// (such as `dtw_timestamp` without DTW), and are left alone.
+    pub(crate) fn shift_timestamps(&mut self, offset: i64) {
+        self.start_timestamp += offset;
+        self.end_timestamp += offset;
+        for token in &mut self.tokens {
+            for timestamp in [
+                &mut token.start_timestamp,
+                &mut token.end_timestamp,
+                &mut token.dtw_timestamp,
+            ] {
+                if *timestamp >= 0 {
+                    *timestamp += offset;
+                }
+            }
+        }
+    }
+
+    /// Split the text of this segment into sentences.
+    ///
+    /// See [`super::Transcript::sentences`] for the heuristic used.
+    pub fn sentences(&self) -> Vec<String> {
+        super::transcript::split_sentences(&self.text)
+    }
+}
+
+/// # Safety
+/// `text` must be null or a valid C string.
+unsafe fn read_text(text: *const c_char, lossy: bool) -> Result<String, WhisperError> {
+    if !text.is_null() {
+        Ok(CStr::from_ptr(text).to_string_lossy().into_owned())
+    } else if lossy {
+        Ok(String::new())
+    } else {
+        Err(WhisperError::NullPointer)
+    }
+}
+
+#[cfg(test)]
+impl WhisperSegmentData {
+    /// A segment with the given text and timestamps, and no tokens.
+    pub(crate) fn for_test(text: &str, start_timestamp: i64, end_timestamp: i64) -> Self {
+        Self {
+            segment: 0,
+            start_timestamp,
+            end_timestamp,
+            text: text.to_string(),
+            no_speech_probability: 0.0,
+            next_segment_speaker_turn: false,
+            tokens: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_shift_timestamps() {
+        let token = |start, end, dtw| WhisperTokenInfo {
+            id: 0,
+            text: String::new(),
+            probability: 1.0,
+            log_probability: 0.0,
+            start_timestamp: start,
+            end_timestamp: end,
+            dtw_timestamp: dtw,
+        };
+        let mut segment = WhisperSegmentData::for_test("", 0, 200);
+        segment.tokens = vec![token(0, 100, 50), token(100, 200, -1), token(-1, -1, -1)];
+        segment.shift_timestamps(3000);
+
+        assert_eq!(segment.start_timestamp, 3000);
+        assert_eq!(segment.end_timestamp, 3200);
+        assert_eq!(segment.tokens[0], token(3000, 3100, 3050));
+        assert_eq!(segment.tokens[1], token(3100, 3200, -1));
+        assert_eq!(segment.tokens[2], token(-1, -1, -1));
+    }
+}
@@ -2,17 +2,34 @@ use crate::{WhisperError, WhisperSegment, WhisperTokenData, WhisperTokenId};
 use std::borrow::Cow;
 use std::ffi::{c_int, CStr};
 use std::fmt;
+use std::marker::PhantomData;
 
 pub struct WhisperToken<'a, 'b: 'a> {
-    segment: &'a WhisperSegment<'b>,
+    // a clone rather than a reference, so tokens can be created from segments that don't outlive them,
+    // see `WhisperState::tokens`
+    segment: WhisperSegment<'b>,
     token_idx: c_int,
+    _segment: PhantomData<&'a WhisperSegment<'b>>,
 }
 
 impl<'a, 'b> WhisperToken<'a, 'b> {
     /// # Safety
     /// You must ensure `token_idx` is in bounds for this [`WhisperSegment`].
     pub(crate) unsafe fn new_unchecked(segment: &'a WhisperSegment<'b>, token_idx: c_int) -> Self {
-        Self { segment, token_idx }
+        Self::from_segment_unchecked(segment.clone(), token_idx)
+    }
+
+    /// # Safety
+    /// You must ensure `token_idx` is in bounds for this [`WhisperSegment`].
+    pub(crate) unsafe fn from_segment_unchecked(
+        segment: WhisperSegment<'b>,
+        token_idx: c_int,
+    ) -> Self {
+        Self {
+            segment,
+            token_idx,
+            _segment: PhantomData,
+        }
     }
 
     /// Get the token ID of this token in its segment.
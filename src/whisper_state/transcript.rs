@@ -0,0 +1,355 @@
+use super::centiseconds_to_duration;
+use crate::{WhisperError, WhisperSegmentData, WhisperState};
+use std::time::Duration;
+
+// silence between two segments, in centiseconds, that is considered a chapter boundary
+const CHAPTER_GAP: i64 = 200;
+
+/// An owned transcript, made of every segment produced by a [`WhisperState`].
+///
+/// Obtain one with [`WhisperState::transcript`].
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Transcript {
+    /// The segments of this transcript, in order.
+    pub segments: Vec<WhisperSegmentData>,
+}
+
+impl Transcript {
+    pub fn new(segments: Vec<WhisperSegmentData>) -> Self {
+        Self { segments }
+    }
+
+    pub(crate) fn from_state(state: &WhisperState) -> Result<Self, WhisperError> {
+        Ok(Self {
+            segments: state.collect_segments()?,
+        })
+    }
+
+    /// The text of every segment, concatenated.
+    pub fn text(&self) -> String {
+        self.segments.iter().map(|s| s.text.as_str()).collect()
+    }
+
+    /// How much audio this transcript covers: the end timestamp of the last segment.
+    ///
+    /// Returns [`Duration::ZERO`] if there are no segments.
+    pub fn duration(&self) -> Duration {
+        self.segments.last().map_or(Duration::ZERO, |s| {
+            centiseconds_to_duration(s.end_timestamp)
+        })
+    }
+
+    /// Split the whole transcript into sentences, ignoring segment boundaries.
+    ///
+    /// This is a simple heuristic, not a full sentence tokenizer:
+    /// a sentence ends at `.`, `!`, `?` or `…` (and their full-width forms)
+    /// followed by whitespace or the end of the text, including any closing quotes or brackets.
+    /// A `.` does not end a sentence if the word before it is a common English abbreviation
+    /// (such as "Dr." or "e.g.") or a single letter initial.
+    ///
+    /// Each sentence is trimmed of surrounding whitespace, and empty sentences are skipped.
+    pub fn sentences(&self) -> Vec<String> {
+        split_sentences(&self.text())
+    }
+
+    /// Render the transcript as two-party dialogue, one line per speaker turn.
+    ///
+    /// Turns are read from [`WhisperSegmentData::next_segment_speaker_turn`],
+    /// which is only ever set when tinydiarize is enabled
+    /// (see [`crate::FullParams::set_tdrz_enable`]).
+    /// Labels simply alternate between `Speaker 1:` and `Speaker 2:` at each turn:
+    /// this is not speaker identification, and conversations with more than two speakers
+    /// will be mislabeled.
+    pub fn to_labeled_text(&self) -> String {
+        let mut out = String::new();
+        let mut speaker = 0;
+        let mut line = String::new();
+
+        for segment in &self.segments {
+            line.push_str(&segment.text);
+            if segment.next_segment_speaker_turn {
+                push_labeled_line(&mut out, speaker, &line);
+                line.clear();
+                speaker = 1 - speaker;
+            }
+        }
+        push_labeled_line(&mut out, speaker, &line);
+
+        out
+    }
+
+    /// Split the transcript into chapters of at least `min_chapter` each.
+    ///
+    /// This is a rough heuristic meant to give a starting point for editing, not a topic model:
+    /// a new chapter may start after a silent gap of 2 seconds or more between segments,
+    /// or at a speaker turn (only detected when tinydiarize is enabled), but only once the
+    /// current chapter is at least `min_chapter` long. The title of each chapter is its
+    /// first sentence (see [`Self::sentences`]), which is often a poor summary of the chapter.
+    ///
+    /// The last chapter may be shorter than `min_chapter`. An empty transcript has no chapters.
+    pub fn auto_chapters(&self, min_chapter: Duration) -> Vec<Chapter> {
+        let mut chapters = Vec::new();
+        let Some(first) = self.segments.first() else {
+            return chapters;
+        };
+
+        let mut chapter_start = first.start_timestamp;
+        let mut chapter_text = String::new();
+        let mut prev: Option<&WhisperSegmentData> = None;
+        for segment in &self.segments {
+            if let Some(prev) = prev {
+                let is_boundary = prev.next_segment_speaker_turn
+                    || segment.start_timestamp - prev.end_timestamp >= CHAPTER_GAP;
+                let long_enough = centiseconds_to_duration(segment.start_timestamp - chapter_start)
+                    >= min_chapter;
+                if is_boundary && long_enough {
+                    chapters.push(Chapter {
+                        start: centiseconds_to_duration(chapter_start),
+                        title: chapter_title(&chapter_text),
+                    });
+                    chapter_start = segment.start_timestamp;
+                    chapter_text.clear();
+                }
+            }
+            chapter_text.push_str(&segment.text);
+            prev = Some(segment);
+        }
+        chapters.push(Chapter {
+            start: centiseconds_to_duration(chapter_start),
+            title: chapter_title(&chapter_text),
+        });
+
+        chapters
+    }
+}
+
+fn chapter_title(text: &str) -> String {
+    split_sentences(text).into_iter().next().unwrap_or_default()
+}
+
+fn push_labeled_line(out: &mut String, speaker: usize, line: &str) {
+    let line = line.trim();
+    if line.is_empty() {
+        return;
+    }
+    if !out.is_empty() {
+        out.push('\n');
+    }
+    out.push_str(&format!("Speaker {}: {}", speaker + 1, line));
+}
+
+/// A chapter of a [`Transcript`], see [`Transcript::auto_chapters`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Chapter {
+    /// When the chapter starts, from the start of the audio.
+    pub start: Duration,
+    /// A provisional title: the first sentence of the chapter.
+    pub title: String,
+}
+
+impl From<Vec<WhisperSegmentData>> for Transcript {
+    fn from(segments: Vec<WhisperSegmentData>) -> Self {
+        Self::new(segments)
+    }
+}
+
+// only words that are never a sentence on their own, so not "no", "co", "mar", "jun", "dec"...
+const ABBREVIATIONS: &[&str] = &[
+    "mr", "mrs", "ms", "dr", "prof", "sr", "jr", "st", "mt", "vs", "etc", "e.g", "i.e", "a.m",
+    "p.m", "approx", "fig", "inc", "ltd", "corp", "feb", "apr", "jul", "aug", "sep", "sept", "oct",
+    "nov",
+];
+
+const TERMINATORS: &[char] = &['.', '!', '?', '…', '。', '！', '？'];
+const CLOSERS: &[char] = &['"', '\'', ')', ']', '}', '”', '’', '»'];
+
+pub(super) fn split_sentences(text: &str) -> Vec<String> {
+    let mut sentences = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((idx, c)) = chars.next() {
+        if !TERMINATORS.contains(&c) {
+            continue;
+        }
+
+        // swallow runs of punctuation ("?!", "...") and closing quotes/brackets
+        let mut end = idx + c.len_utf8();
+        while let Some(&(next_idx, next)) = chars.peek() {
+            if TERMINATORS.contains(&next) || CLOSERS.contains(&next) {
+                end = next_idx + next.len_utf8();
+                chars.next();
+            } else {
+                break;
+            }
+        }
+
+        let at_boundary = chars.peek().is_none_or(|&(_, next)| next.is_whitespace());
+        if !at_boundary || (c == '.' && is_abbreviation(&text[start..idx], &text[end..])) {
+            continue;
+        }
+
+        push_sentence(&mut sentences, &text[start..end]);
+        start = end;
+    }
+    push_sentence(&mut sentences, &text[start..]);
+
+    sentences
+}
+
+fn is_abbreviation(before_dot: &str, after_dot: &str) -> bool {
+    let word = before_dot
+        .rsplit(char::is_whitespace)
+        .next()
+        .unwrap_or_default()
+        .trim_start_matches(|c: char| !c.is_alphanumeric());
+    let mut word_chars = word.chars();
+    match (word_chars.next(), word_chars.next()) {
+        // single letter initials, such as the "J" in "J. R. R. Tolkien", which are followed by
+        // another initial or a surname. "I" and "A" are far more likely to end a sentence.
+        (Some(c), None) => {
+            c.is_uppercase()
+                && c != 'I'
+                && c != 'A'
+                && after_dot
+                    .trim_start()
+                    .chars()
+                    .next()
+                    .is_some_and(char::is_uppercase)
+        }
+        _ => ABBREVIATIONS.contains(&word.to_lowercase().as_str()),
+    }
+}
+
+fn push_sentence(sentences: &mut Vec<String>, sentence: &str) {
+    let sentence = sentence.trim();
+    if !sentence.is_empty() {
+        sentences.push(sentence.to_string());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn segment(text: &str, turn: bool) -> WhisperSegmentData {
+        timed_segment(text, 0, 0, turn)
+    }
+
+    fn timed_segment(text: &str, start: i64, end: i64, turn: bool) -> WhisperSegmentData {
+        WhisperSegmentData {
+            next_segment_speaker_turn: turn,
+            ..WhisperSegmentData::for_test(text, start, end)
+        }
+    }
+
+    #[test]
+    fn test_to_labeled_text() {
+        let transcript = Transcript::new(vec![
+            segment(" How are you?", true),
+            segment(" Good,", false),
+            segment(" thanks.", true),
+            segment(" Great.", false),
+        ]);
+        assert_eq!(
+            transcript.to_labeled_text(),
+            "Speaker 1: How are you?\nSpeaker 2: Good, thanks.\nSpeaker 1: Great."
+        );
+    }
+
+    #[test]
+    fn test_auto_chapters() {
+        let transcript = Transcript::new(vec![
+            timed_segment(" Welcome to the show. Today,", 0, 500, false),
+            // short gap, not a boundary
+            timed_segment(" we talk about bees.", 550, 1000, false),
+            // long gap, but the chapter is still too short
+            timed_segment(" First, hives.", 1500, 2000, true),
+            // speaker turn
+            timed_segment(" Hives are great. Really.", 2000, 3000, false),
+            // long gap
+            timed_segment(" Thanks for listening.", 4500, 5000, false),
+        ]);
+        assert_eq!(
+            transcript.auto_chapters(Duration::from_secs(20)),
+            vec![
+                Chapter {
+                    start: Duration::ZERO,
+                    title: "Welcome to the show.".to_string(),
+                },
+                Chapter {
+                    start: Duration::from_secs(20),
+                    title: "Hives are great.".to_string(),
+                },
+                Chapter {
+                    start: Duration::from_secs(45),
+                    title: "Thanks for listening.".to_string(),
+                },
+            ]
+        );
+        assert_eq!(transcript.duration(), Duration::from_secs(50));
+        assert!(Transcript::default()
+            .auto_chapters(Duration::from_secs(10))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_split_sentences_basic() {
+        assert_eq!(
+            split_sentences(" Hello there. How are you? I'm fine!"),
+            vec!["Hello there.", "How are you?", "I'm fine!"]
+        );
+    }
+
+    #[test]
+    fn test_split_sentences_abbreviations() {
+        assert_eq!(
+            split_sentences("Dr. Smith met J. Doe at 3 p.m. yesterday. It went well."),
+            vec!["Dr. Smith met J. Doe at 3 p.m. yesterday.", "It went well."]
+        );
+    }
+
+    #[test]
+    fn test_split_sentences_short_words() {
+        assert_eq!(
+            split_sentences("The answer is no. We left. So did I. Then I got an A. Great."),
+            vec![
+                "The answer is no.",
+                "We left.",
+                "So did I.",
+                "Then I got an A.",
+                "Great."
+            ]
+        );
+        assert_eq!(
+            split_sentences("I read J. R. R. Tolkien. It's about plan b. then more."),
+            vec![
+                "I read J. R. R. Tolkien.",
+                "It's about plan b.",
+                "then more."
+            ]
+        );
+    }
+
+    #[test]
+    fn test_split_sentences_punctuation_runs() {
+        assert_eq!(
+            split_sentences("Wait... what?! \"Really.\" Yes. Version 1.5 works"),
+            vec![
+                "Wait...",
+                "what?!",
+                "\"Really.\"",
+                "Yes.",
+                "Version 1.5 works"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_split_sentences_empty() {
+        assert!(split_sentences("").is_empty());
+        assert!(split_sentences("   ").is_empty());
+    }
+}
@@ -0,0 +1,19 @@
+/// A word assembled from one or more tokens of a [`crate::WhisperSegment`].
+///
+/// See [`crate::WhisperSegment::words`].
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Word {
+    /// The text of this word, without its leading space.
+    pub text: String,
+    /// Start time in centiseconds (10s of milliseconds), taken from DTW token timestamps.
+    ///
+    /// Only word-accurate if DTW is enabled, see [`crate::WhisperSegment::words`].
+    pub start_centiseconds: i64,
+    /// End time in centiseconds (10s of milliseconds), taken from DTW token timestamps.
+    ///
+    /// Only word-accurate if DTW is enabled, see [`crate::WhisperSegment::words`].
+    pub end_centiseconds: i64,
+    /// Mean probability of the tokens making up this word, from 0.0 to 1.0.
+    pub confidence: f32,
+}
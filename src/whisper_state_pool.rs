@@ -0,0 +1,144 @@
+use crate::{WhisperContext, WhisperError, WhisperState};
+use std::ops::{Deref, DerefMut};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+
+/// A fixed number of [`WhisperState`]s sharing one model, handed out one caller at a time.
+///
+/// Each state can run [`WhisperState::full`] concurrently with the others,
+/// so a pool of N states lets N requests be transcribed at once on a single loaded model.
+///
+/// The pool is cheap to clone, and clones share the same states.
+///
+/// # Examples
+/// ```no_run
+/// # use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters, WhisperStatePool};
+/// let ctx = WhisperContext::new_with_params("model.bin", WhisperContextParameters::default())?;
+/// let pool = WhisperStatePool::new(&ctx, 4)?;
+///
+/// std::thread::scope(|s| {
+///     for _ in 0..8 {
+///         let pool = pool.clone();
+///         s.spawn(move || {
+///             // blocks until one of the 4 states is free
+///             let mut state = pool.acquire();
+///             let params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+///             state.full(params, &[0.0; 16000]).unwrap();
+///         });
+///     }
+/// });
+/// # Ok::<(), whisper_rs::WhisperError>(())
+/// ```
+#[derive(Clone)]
+pub struct WhisperStatePool {
+    inner: Arc<PoolInner>,
+}
+
+struct PoolInner {
+    states: Mutex<Vec<WhisperState>>,
+    available: Condvar,
+}
+
+impl PoolInner {
+    fn lock(&self) -> MutexGuard<'_, Vec<WhisperState>> {
+        // a panic while holding the lock can't leave the Vec in an invalid state
+        self.states.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl WhisperStatePool {
+    /// Create a pool of `size` states for `ctx`.
+    ///
+    /// # Arguments
+    /// * ctx: The context to create the states from.
+    /// * size: How many states to create, which is how many callers can transcribe at once.
+    ///   Must be at least 1, or [`Self::acquire`] would block forever.
+    ///
+    /// # Returns
+    /// Ok(Self) on success, Err(WhisperError::InvalidPoolSize) if `size` is 0,
+    /// Err(WhisperError) if any state failed to be created.
+    pub fn new(ctx: &WhisperContext, size: usize) -> Result<Self, WhisperError> {
+        if size == 0 {
+            return Err(WhisperError::InvalidPoolSize);
+        }
+        let states = (0..size)
+            .map(|_| ctx.create_state())
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self {
+            inner: Arc::new(PoolInner {
+                states: Mutex::new(states),
+                available: Condvar::new(),
+            }),
+        })
+    }
+
+    /// Take a state out of the pool, blocking until one is available.
+    ///
+    /// The state is returned to the pool when the [`PooledState`] is dropped.
+    pub fn acquire(&self) -> PooledState {
+        let mut states = self.inner.lock();
+        loop {
+            if let Some(state) = states.pop() {
+                return PooledState::new(state, self.inner.clone());
+            }
+            states = self
+                .inner
+                .available
+                .wait(states)
+                .unwrap_or_else(|e| e.into_inner());
+        }
+    }
+
+    /// Take a state out of the pool if one is available, without blocking.
+    pub fn try_acquire(&self) -> Option<PooledState> {
+        let state = self.inner.lock().pop()?;
+        Some(PooledState::new(state, self.inner.clone()))
+    }
+
+    /// How many states are currently in the pool, waiting to be acquired.
+    pub fn available(&self) -> usize {
+        self.inner.lock().len()
+    }
+}
+
+/// A [`WhisperState`] borrowed from a [`WhisperStatePool`], returned to it on drop.
+///
+/// Dereferences to [`WhisperState`]. Before being returned, the state is [`WhisperState::reset`],
+/// so the next caller's transcription doesn't use this one's text as context.
+pub struct PooledState {
+    // only ever `None` while being dropped
+    state: Option<WhisperState>,
+    pool: Arc<PoolInner>,
+}
+
+impl PooledState {
+    fn new(state: WhisperState, pool: Arc<PoolInner>) -> Self {
+        Self {
+            state: Some(state),
+            pool,
+        }
+    }
+}
+
+impl Deref for PooledState {
+    type Target = WhisperState;
+
+    fn deref(&self) -> &WhisperState {
+        self.state.as_ref().expect("pooled state used after drop")
+    }
+}
+
+impl DerefMut for PooledState {
+    fn deref_mut(&mut self) -> &mut WhisperState {
+        self.state.as_mut().expect("pooled state used after drop")
+    }
+}
+
+impl Drop for PooledState {
+    fn drop(&mut self) {
+        if let Some(mut state) = self.state.take() {
+            state.reset();
+            self.pool.lock().push(state);
+            self.pool.available.notify_one();
+        }
+    }
+}
@@ -0,0 +1,281 @@
+use crate::{FullParams, WhisperError, WhisperSegmentData, WhisperState};
+use std::time::Duration;
+
+const SAMPLES_PER_CENTISECOND: usize = whisper_rs_sys::WHISPER_SAMPLE_RATE as usize / 100;
+
+/// A segment returned by [`StreamingTranscriber::step`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum StreamingSegment {
+    /// A segment that ends before the overlap region of the window.
+    /// It will not be revised, and won't be returned again.
+    Stable(WhisperSegmentData),
+    /// A segment that ends within the overlap region of the window.
+    /// Its audio is transcribed again by the next step, so it may be revised or replaced.
+    Tentative(WhisperSegmentData),
+}
+
+impl StreamingSegment {
+    /// Get the segment, whether it's stable or not.
+    pub fn data(&self) -> &WhisperSegmentData {
+        match self {
+            Self::Stable(data) | Self::Tentative(data) => data,
+        }
+    }
+
+    /// Whether this segment is final.
+    pub fn is_stable(&self) -> bool {
+        matches!(self, Self::Stable(_))
+    }
+}
+
+/// Transcribe audio as it arrives, by repeatedly running [`WhisperState::full`]
+/// on a sliding window of the most recent audio.
+///
+/// Each [`Self::step`] transcribes the whole window again. Segments that end before the last
+/// `overlap` of the window are returned as [`StreamingSegment::Stable`], and their audio is
+/// dropped from the window. The rest are returned as [`StreamingSegment::Tentative`]:
+/// they are likely cut off by the end of the audio received so far,
+/// so they'll be transcribed again (with more context) by the next step.
+///
+/// Timestamps of returned segments and their tokens are relative to the start of the stream,
+/// not to the start of the window.
+///
+/// If audio arrives faster than it should be transcribed, buffer it with [`Self::push_audio`]
+/// and only transcribe when convenient with [`Self::poll_segments`], which returns stable segments only.
+/// Call [`Self::flush`] at the end of the stream to get the remaining segments.
+pub struct StreamingTranscriber<'a, 'b> {
+    state: WhisperState,
+    params: FullParams<'a, 'b>,
+    window: Vec<f32>,
+    // start of the window, in centiseconds since the start of the stream
+    window_start: i64,
+    max_window_samples: usize,
+    overlap_samples: usize,
+    // samples pushed since the window was last transcribed
+    unprocessed_samples: usize,
+}
+
+impl<'a, 'b> StreamingTranscriber<'a, 'b> {
+    /// Create a new streaming transcriber.
+    ///
+    /// `no_context` is enabled on `params`, as the audio of tentative segments is transcribed
+    /// more than once, and carrying the decoder context over would repeat their text.
+    ///
+    /// # Arguments
+    /// * state: The state to run transcription on.
+    /// * params: The parameters used for every call to [`WhisperState::full`].
+    /// * window: The maximum amount of audio transcribed per step.
+    ///   If no stable segment was found within this much audio, the oldest audio is discarded,
+    ///   and the segments starting in it are returned as stable, even if they end in the overlap.
+    /// * overlap: How much audio at the end of the window is considered unreliable.
+    ///
+    /// # Returns
+    /// Ok(Self) on success, or [`WhisperError::InvalidStreamingWindow`] if `window` is zero
+    /// or `overlap` is not shorter than `window`, as no segment could ever become stable.
+    pub fn new(
+        state: WhisperState,
+        mut params: FullParams<'a, 'b>,
+        window: Duration,
+        overlap: Duration,
+    ) -> Result<Self, WhisperError> {
+        let max_window_samples = duration_to_samples(window);
+        let overlap_samples = duration_to_samples(overlap);
+        if max_window_samples == 0 || overlap_samples >= max_window_samples {
+            return Err(WhisperError::InvalidStreamingWindow);
+        }
+
+        params.set_no_context(true);
+        Ok(Self {
+            state,
+            params,
+            window: Vec::new(),
+            window_start: 0,
+            max_window_samples,
+            overlap_samples,
+            unprocessed_samples: 0,
+        })
+    }
+
+    /// Append `samples` to the window and transcribe it.
+    ///
+    /// # Arguments
+    /// * samples: The newly received audio. Must be 16KHz mono.
+    ///
+    /// # Returns
+    /// Ok(Vec<StreamingSegment>) on success, with every stable segment found by this step
+    /// followed by the tentative ones, Err(WhisperError) on failure.
+    pub fn step(&mut self, samples: &[f32]) -> Result<Vec<StreamingSegment>, WhisperError> {
+        self.window.extend_from_slice(samples);
+        self.unprocessed_samples = 0;
+        self.state.full(self.params.clone(), &self.window)?;
+
+        let mut segments = Vec::new();
+        for segment in self.state.as_iter() {
+            // make the segment and its tokens relative to the start of the stream
+            let mut data = segment.to_owned()?;
+            data.shift_timestamps(self.window_start);
+            segments.push(data);
+        }
+
+        let window_end = self.window_start + (self.window.len() / SAMPLES_PER_CENTISECOND) as i64;
+        let stable_until = window_end - (self.overlap_samples / SAMPLES_PER_CENTISECOND) as i64;
+        // never let the window grow past its maximum, even if it means dropping audio
+        // that no segment was stable in
+        let overflow = self.window.len().saturating_sub(self.max_window_samples);
+        let overflow = overflow.div_ceil(SAMPLES_PER_CENTISECOND) * SAMPLES_PER_CENTISECOND;
+        let dropped_until = self.window_start + (overflow / SAMPLES_PER_CENTISECOND) as i64;
+        let segments = classify_segments(segments, stable_until, dropped_until);
+
+        // drop the audio covered by stable segments, so it isn't transcribed again
+        let committed_until = segments
+            .iter()
+            .rev()
+            .find(|s| s.is_stable())
+            .map(|s| s.data().end_timestamp)
+            .unwrap_or(self.window_start);
+        let drain = ((committed_until - self.window_start).max(0) as usize
+            * SAMPLES_PER_CENTISECOND)
+            .max(overflow)
+            .min(self.window.len());
+        self.window.drain(..drain);
+        self.window_start += (drain / SAMPLES_PER_CENTISECOND) as i64;
+
+        Ok(segments)
+    }
+
+    /// Append `samples` to the window without transcribing it.
+    ///
+    /// # Arguments
+    /// * samples: The newly received audio. Must be 16KHz mono.
+    pub fn push_audio(&mut self, samples: &[f32]) {
+        self.window.extend_from_slice(samples);
+        self.unprocessed_samples += samples.len();
+    }
+
+    /// Transcribe the audio pushed with [`Self::push_audio`], if there is any.
+    ///
+    /// Unlike [`Self::step`], only stable segments are returned,
+    /// so every segment is returned exactly once and never revised.
+    ///
+    /// # Returns
+    /// Ok(Vec<WhisperSegmentData>) on success, empty if no audio was pushed since the last call,
+    /// Err(WhisperError) on failure.
+    pub fn poll_segments(&mut self) -> Result<Vec<WhisperSegmentData>, WhisperError> {
+        if self.unprocessed_samples == 0 {
+            return Ok(Vec::new());
+        }
+        Ok(self
+            .step(&[])?
+            .into_iter()
+            .filter_map(|segment| match segment {
+                StreamingSegment::Stable(data) => Some(data),
+                StreamingSegment::Tentative(_) => None,
+            })
+            .collect())
+    }
+
+    /// Transcribe the rest of the window and empty it, for the end of the stream.
+    ///
+    /// The remaining audio won't be followed by anything, so all of its segments are final,
+    /// including those that would be tentative in [`Self::step`].
+    ///
+    /// # Returns
+    /// Ok(Vec<WhisperSegmentData>) on success, Err(WhisperError) on failure.
+    pub fn flush(&mut self) -> Result<Vec<WhisperSegmentData>, WhisperError> {
+        if self.window.is_empty() {
+            return Ok(Vec::new());
+        }
+        let segments = self
+            .step(&[])?
+            .into_iter()
+            .map(|segment| match segment {
+                StreamingSegment::Stable(data) | StreamingSegment::Tentative(data) => data,
+            })
+            .collect();
+        self.window_start += (self.window.len() / SAMPLES_PER_CENTISECOND) as i64;
+        self.window.clear();
+        Ok(segments)
+    }
+
+    /// Get the underlying state.
+    pub fn state(&self) -> &WhisperState {
+        &self.state
+    }
+
+    /// Consume the transcriber, returning the underlying state.
+    pub fn into_state(self) -> WhisperState {
+        self.state
+    }
+}
+
+fn duration_to_samples(duration: Duration) -> usize {
+    (duration.as_millis() as usize).saturating_mul(SAMPLES_PER_CENTISECOND / 10)
+}
+
+/// Split segments into stable ones, which end by `stable_until`, and tentative ones.
+///
+/// Segments starting before `dropped_until` are stable wherever they end, as their audio
+/// is about to be dropped from the window and they won't be transcribed again.
+fn classify_segments(
+    segments: Vec<WhisperSegmentData>,
+    stable_until: i64,
+    dropped_until: i64,
+) -> Vec<StreamingSegment> {
+    let mut stable = true;
+    segments
+        .into_iter()
+        .map(|segment| {
+            // once a segment is tentative, every segment after it must be too
+            stable &=
+                segment.end_timestamp <= stable_until || segment.start_timestamp < dropped_until;
+            if stable {
+                StreamingSegment::Stable(segment)
+            } else {
+                StreamingSegment::Tentative(segment)
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn segment(start_timestamp: i64, end_timestamp: i64) -> WhisperSegmentData {
+        WhisperSegmentData::for_test("", start_timestamp, end_timestamp)
+    }
+
+    #[test]
+    fn test_classify_segments() {
+        let segments = vec![segment(0, 200), segment(200, 400), segment(400, 450)];
+        let classified = classify_segments(segments.clone(), 400, 0);
+        assert_eq!(
+            classified,
+            vec![
+                StreamingSegment::Stable(segments[0].clone()),
+                StreamingSegment::Stable(segments[1].clone()),
+                StreamingSegment::Tentative(segments[2].clone()),
+            ]
+        );
+        assert!(classify_segments(segments.clone(), 100, 0)
+            .iter()
+            .all(|s| !s.is_stable()));
+
+        // the window overflowed: the segments starting in the dropped audio are final
+        let classified = classify_segments(segments.clone(), 100, 300);
+        assert_eq!(
+            classified,
+            vec![
+                StreamingSegment::Stable(segments[0].clone()),
+                StreamingSegment::Stable(segments[1].clone()),
+                StreamingSegment::Tentative(segments[2].clone()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_duration_to_samples() {
+        assert_eq!(duration_to_samples(Duration::from_secs(2)), 32000);
+        assert_eq!(duration_to_samples(Duration::from_millis(500)), 8000);
+    }
+}
@@ -1,9 +1,12 @@
-use crate::WhisperError;
-use std::ffi::{c_char, CString};
+use crate::{WhisperError, WhisperInnerContext};
+use std::ffi::{c_char, c_void, CString};
+use std::ops::Range;
 use std::os::raw::c_int;
+use std::sync::Arc;
 use whisper_rs_sys::{
-    whisper_vad_context, whisper_vad_context_params, whisper_vad_detect_speech, whisper_vad_free,
-    whisper_vad_free_segments, whisper_vad_init_from_file_with_params, whisper_vad_n_probs,
+    whisper_model_loader, whisper_vad_context, whisper_vad_context_params,
+    whisper_vad_detect_speech, whisper_vad_free, whisper_vad_free_segments,
+    whisper_vad_init_from_file_with_params, whisper_vad_init_with_params, whisper_vad_n_probs,
     whisper_vad_params, whisper_vad_probs, whisper_vad_segments, whisper_vad_segments_from_probs,
     whisper_vad_segments_from_samples, whisper_vad_segments_get_segment_t0,
     whisper_vad_segments_get_segment_t1, whisper_vad_segments_n_segments,
@@ -87,6 +90,37 @@ impl WhisperVadParams {
         self.params.samples_overlap = samples_overlap;
     }
 
+    /// Check that every parameter is within its valid range.
+    ///
+    /// This is done automatically by [`WhisperVadContext::segments_from_probabilities`]
+    /// and [`WhisperVadContext::segments_from_samples`].
+    ///
+    /// # Errors
+    /// [`WhisperError::InvalidVadParams`] if:
+    /// * the threshold isn't between 0.0 and 1.0
+    /// * the minimum speech duration, minimum silence duration or speech padding is negative
+    /// * the maximum speech duration isn't positive
+    /// * the samples overlap is negative or not finite
+    pub fn validate(&self) -> Result<(), WhisperError> {
+        let p = &self.params;
+        let reason = if !(0.0..=1.0).contains(&p.threshold) {
+            "threshold must be between 0.0 and 1.0"
+        } else if p.min_speech_duration_ms < 0 {
+            "minimum speech duration must not be negative"
+        } else if p.min_silence_duration_ms < 0 {
+            "minimum silence duration must not be negative"
+        } else if p.max_speech_duration_s.is_nan() || p.max_speech_duration_s <= 0.0 {
+            "maximum speech duration must be positive"
+        } else if p.speech_pad_ms < 0 {
+            "speech padding must not be negative"
+        } else if !p.samples_overlap.is_finite() || p.samples_overlap < 0.0 {
+            "samples overlap must be finite and not negative"
+        } else {
+            return Ok(());
+        };
+        Err(WhisperError::InvalidVadParams { reason })
+    }
+
     pub(crate) fn into_inner(self) -> whisper_vad_params {
         self.params
     }
@@ -138,9 +172,19 @@ impl WhisperVadContextParams {
 /// A handle to use `whisper.cpp`'s built in VAD standalone.
 ///
 /// You probably want to use [`Self::segments_from_samples`].
+///
+/// # Thread safety
+/// `WhisperVadContext` is `Send` and `Sync`: it can be moved to another thread,
+/// or shared behind an `Arc<Mutex<_>>`.
+/// Running VAD takes `&mut self`, so one context can only process one input at a time;
+/// create one context per thread to run VAD in parallel.
 pub struct WhisperVadContext {
     ptr: *mut whisper_vad_context,
+    // keeps the main context alive for as long as this one, see `WhisperContext::with_vad`
+    _parent: Option<Arc<WhisperInnerContext>>,
 }
+// SAFETY: the context isn't tied to the thread that created it,
+// and every method that mutates it takes `&mut self`
 unsafe impl Send for WhisperVadContext {}
 unsafe impl Sync for WhisperVadContext {}
 
@@ -155,10 +199,99 @@ impl WhisperVadContext {
         if ptr.is_null() {
             Err(WhisperError::NullPointer)
         } else {
-            Ok(Self { ptr })
+            Ok(Self { ptr, _parent: None })
         }
     }
 
+    /// Create a new VAD context from a buffer containing the model,
+    /// such as one embedded in the binary with `include_bytes!`.
+    ///
+    /// The buffer is only read while loading, and may be dropped once this returns.
+    ///
+    /// # Arguments
+    /// * buffer: The buffer containing the model.
+    /// * params: The parameters to create the context with.
+    ///
+    /// # Returns
+    /// Ok(Self) on success, Err([`WhisperError::NullPointer`]) on failure.
+    ///
+    /// # C++ equivalent
+    /// `struct whisper_vad_context * whisper_vad_init_with_params(struct whisper_model_loader * loader, struct whisper_vad_context_params params);`
+    pub fn new_from_buffer(
+        buffer: &[u8],
+        params: WhisperVadContextParams,
+    ) -> Result<Self, WhisperError> {
+        struct BufferReader<'a> {
+            buffer: &'a [u8],
+            offset: usize,
+        }
+
+        unsafe extern "C" fn read(
+            ctx: *mut c_void,
+            output: *mut c_void,
+            read_size: usize,
+        ) -> usize {
+            let reader = &mut *(ctx as *mut BufferReader);
+            let n = read_size.min(reader.buffer.len() - reader.offset);
+            std::ptr::copy_nonoverlapping(
+                reader.buffer.as_ptr().add(reader.offset),
+                output as *mut u8,
+                n,
+            );
+            reader.offset += n;
+            n
+        }
+
+        unsafe extern "C" fn eof(ctx: *mut c_void) -> bool {
+            let reader = &*(ctx as *const BufferReader);
+            reader.offset >= reader.buffer.len()
+        }
+
+        // the buffer is borrowed, so there's nothing to close
+        unsafe extern "C" fn close(_: *mut c_void) {}
+
+        let mut reader = BufferReader { buffer, offset: 0 };
+        let mut loader = whisper_model_loader {
+            context: &mut reader as *mut BufferReader as *mut c_void,
+            read: Some(read),
+            eof: Some(eof),
+            close: Some(close),
+        };
+        let ptr = unsafe { whisper_vad_init_with_params(&mut loader, params.into_inner()) };
+
+        if ptr.is_null() {
+            Err(WhisperError::NullPointer)
+        } else {
+            Ok(Self { ptr, _parent: None })
+        }
+    }
+
+    /// Create a new VAD context without blocking the async runtime,
+    /// by loading the model on tokio's blocking thread pool.
+    ///
+    /// Requires the `async` feature, and must be called from within a tokio runtime.
+    #[cfg(feature = "async")]
+    pub async fn new_async(
+        model_path: impl AsRef<str> + Send + 'static,
+        params: WhisperVadContextParams,
+    ) -> Result<Self, WhisperError> {
+        crate::utilities::spawn_blocking(move || Self::new(model_path.as_ref(), params)).await
+    }
+
+    /// Create a VAD context on the same device as `parent`, keeping `parent` alive until it is dropped.
+    pub(crate) fn new_with_parent(
+        model_path: &str,
+        mut params: WhisperVadContextParams,
+        parent: Arc<WhisperInnerContext>,
+    ) -> Result<Self, WhisperError> {
+        params.set_use_gpu(parent.use_gpu);
+        params.set_gpu_device(parent.gpu_device);
+
+        let mut ctx = Self::new(model_path, params)?;
+        ctx._parent = Some(parent);
+        Ok(ctx)
+    }
+
     /// Detect speech in `samples`. Call [`Self::segments_from_probabilities`] to finish the pipeline.
     ///
     /// # Errors
@@ -188,11 +321,13 @@ impl WhisperVadContext {
     /// Finish running the VAD pipeline and return segment details.
     ///
     /// # Errors
-    /// The only possible error is [`WhisperError::NullPointer`].
+    /// [`WhisperError::InvalidVadParams`] if `params` are out of range (see [`WhisperVadParams::validate`]),
+    /// or [`WhisperError::NullPointer`].
     pub fn segments_from_probabilities(
         &mut self,
         params: WhisperVadParams,
     ) -> Result<WhisperVadSegments, WhisperError> {
+        params.validate()?;
         let ptr = unsafe { whisper_vad_segments_from_probs(self.ptr, params.into_inner()) };
 
         if ptr.is_null() {
@@ -206,12 +341,14 @@ impl WhisperVadContext {
     /// This calls both [`Self::detect_speech`] and [`Self::segments_from_probabilities`] behind the scenes.
     ///
     /// # Errors
-    /// The only possible error is [`WhisperError::NullPointer`].
+    /// [`WhisperError::InvalidVadParams`] if `params` are out of range (see [`WhisperVadParams::validate`]),
+    /// or [`WhisperError::NullPointer`].
     pub fn segments_from_samples(
         &mut self,
         params: WhisperVadParams,
         samples: &[f32],
     ) -> Result<WhisperVadSegments, WhisperError> {
+        params.validate()?;
         let (sample_ptr, sample_len) = (samples.as_ptr(), samples.len() as c_int);
         let ptr = unsafe {
             whisper_vad_segments_from_samples(self.ptr, params.into_inner(), sample_ptr, sample_len)
@@ -232,12 +369,20 @@ impl Drop for WhisperVadContext {
 }
 
 /// You can obtain this struct from a [`WhisperVadContext`].
+///
+/// Prefer iterating with [`Self::iter`] (or `&segments`), which can be done any number of times.
+/// Iterating `WhisperVadSegments` itself advances an internal cursor, so segments that were
+/// already yielded won't be yielded again.
 pub struct WhisperVadSegments {
     ptr: *mut whisper_vad_segments,
     segment_count: c_int,
     iter_idx: c_int,
 }
 
+// SAFETY: the segments are a plain array owned by this struct, with no references to the context
+unsafe impl Send for WhisperVadSegments {}
+unsafe impl Sync for WhisperVadSegments {}
+
 impl WhisperVadSegments {
     fn new(ptr: *mut whisper_vad_segments) -> Self {
         let segment_count = unsafe { whisper_vad_segments_n_segments(ptr) };
@@ -280,8 +425,82 @@ impl WhisperVadSegments {
 
         Some(WhisperVadSegment { start, end })
     }
+
+    /// Iterate over the segments without consuming or advancing `self`.
+    pub fn iter(&self) -> WhisperVadSegmentsIter<'_> {
+        WhisperVadSegmentsIter {
+            segments: self,
+            idx: 0,
+        }
+    }
+
+    /// Fuse segments separated by less than `max_gap_centiseconds` of silence.
+    ///
+    /// Each returned segment starts at the start of the first segment in its group,
+    /// and ends at the end of the last one.
+    pub fn merged(&self, max_gap_centiseconds: f32) -> Vec<WhisperVadSegment> {
+        merge_segments(self.iter(), max_gap_centiseconds)
+    }
+
+    /// Copy the audio of every segment out of `source`, one buffer per segment.
+    ///
+    /// # Arguments
+    /// * source: The audio VAD was run on, starting at timestamp 0.
+    /// * sample_rate: The sample rate of `source`, in Hz.
+    ///
+    /// # Returns
+    /// The samples of each segment, clamped to the bounds of `source`.
+    pub fn extract_samples(&self, source: &[f32], sample_rate: u32) -> Vec<Vec<f32>> {
+        self.iter()
+            .map(|segment| source[segment.clamped_sample_range(source.len(), sample_rate)].to_vec())
+            .collect()
+    }
+
+    /// Copy the audio of every segment out of `source` into a single buffer, dropping the gaps between them.
+    ///
+    /// See [`Self::extract_samples`].
+    pub fn extract_concatenated(&self, source: &[f32], sample_rate: u32) -> Vec<f32> {
+        let mut out = Vec::new();
+        for segment in self.iter() {
+            out.extend_from_slice(&source[segment.clamped_sample_range(source.len(), sample_rate)]);
+        }
+        out
+    }
 }
 
+impl<'a> IntoIterator for &'a WhisperVadSegments {
+    type Item = WhisperVadSegment;
+    type IntoIter = WhisperVadSegmentsIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterates over the segments of a [`WhisperVadSegments`] by reference, see [`WhisperVadSegments::iter`].
+pub struct WhisperVadSegmentsIter<'a> {
+    segments: &'a WhisperVadSegments,
+    idx: c_int,
+}
+
+impl Iterator for WhisperVadSegmentsIter<'_> {
+    type Item = WhisperVadSegment;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let segment = self.segments.get_segment(self.idx)?;
+        self.idx += 1;
+        Some(segment)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = (self.segments.segment_count - self.idx).max(0) as usize;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for WhisperVadSegmentsIter<'_> {}
+
+/// Advances an internal cursor, so it can only be walked once. Prefer [`WhisperVadSegments::iter`].
 impl Iterator for WhisperVadSegments {
     type Item = WhisperVadSegment;
 
@@ -293,6 +512,7 @@ impl Iterator for WhisperVadSegments {
 }
 
 #[derive(Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct WhisperVadSegment {
     /// Start timestamp of this segment in centiseconds.
     pub start: f32,
@@ -300,8 +520,130 @@ pub struct WhisperVadSegment {
     pub end: f32,
 }
 
+fn merge_segments(
+    segments: impl IntoIterator<Item = WhisperVadSegment>,
+    max_gap_centiseconds: f32,
+) -> Vec<WhisperVadSegment> {
+    let mut merged: Vec<WhisperVadSegment> = Vec::new();
+    for segment in segments {
+        match merged.last_mut() {
+            Some(last) if segment.start - last.end < max_gap_centiseconds => {
+                last.end = last.end.max(segment.end);
+            }
+            _ => merged.push(segment),
+        }
+    }
+    merged
+}
+
+impl WhisperVadSegment {
+    /// Start timestamp of this segment in seconds.
+    pub fn start_seconds(&self) -> f32 {
+        self.start / 100.0
+    }
+
+    /// End timestamp of this segment in seconds.
+    pub fn end_seconds(&self) -> f32 {
+        self.end / 100.0
+    }
+
+    /// Length of this segment in seconds.
+    pub fn duration_seconds(&self) -> f32 {
+        (self.end - self.start) / 100.0
+    }
+
+    /// The indices of the samples covered by this segment, rounded to the nearest sample.
+    ///
+    /// This isn't clamped to the length of any buffer,
+    /// see [`WhisperVadSegments::extract_samples`] for that.
+    ///
+    /// # Arguments
+    /// * sample_rate: The sample rate of the audio VAD was run on, in Hz.
+    pub fn sample_range(&self, sample_rate: u32) -> Range<usize> {
+        self.clamped_sample_range(usize::MAX, sample_rate)
+    }
+
+    /// The samples covered by this segment, rounded to the nearest sample and clamped to `0..n_samples`.
+    fn clamped_sample_range(&self, n_samples: usize, sample_rate: u32) -> Range<usize> {
+        crate::utilities::centiseconds_to_sample_range(
+            self.start as f64,
+            self.end as f64,
+            sample_rate,
+            n_samples,
+        )
+    }
+}
+
 impl Drop for WhisperVadSegments {
     fn drop(&mut self) {
         unsafe { whisper_vad_free_segments(self.ptr) }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_merge_segments() {
+        let segment = |start, end| WhisperVadSegment { start, end };
+        let merged = merge_segments(
+            [
+                segment(0.0, 100.0),
+                segment(120.0, 200.0),
+                segment(250.0, 300.0),
+                segment(340.0, 400.0),
+            ],
+            45.0,
+        );
+        let merged = merged.iter().map(|s| (s.start, s.end)).collect::<Vec<_>>();
+        assert_eq!(merged, [(0.0, 200.0), (250.0, 400.0)]);
+        assert!(merge_segments([], 10.0).is_empty());
+    }
+
+    #[test]
+    fn test_sample_range() {
+        // the conversion itself is tested with `centiseconds_to_sample_range`
+        let segment = WhisperVadSegment {
+            start: 150.0,
+            end: 250.0,
+        };
+        assert_eq!(segment.clamped_sample_range(32000, 16000), 24000..32000);
+        assert_eq!(segment.sample_range(16000), 24000..40000);
+    }
+
+    #[test]
+    fn test_validate_params() {
+        assert!(WhisperVadParams::default().validate().is_ok());
+
+        let mut params = WhisperVadParams::default();
+        params.set_threshold(1.5);
+        assert!(matches!(
+            params.validate(),
+            Err(WhisperError::InvalidVadParams { .. })
+        ));
+
+        let mut params = WhisperVadParams::default();
+        params.set_min_speech_duration(-1);
+        assert!(params.validate().is_err());
+
+        let mut params = WhisperVadParams::default();
+        params.set_max_speech_duration(0.0);
+        assert!(params.validate().is_err());
+
+        let mut params = WhisperVadParams::default();
+        params.set_samples_overlap(f32::NAN);
+        assert!(params.validate().is_err());
+    }
+
+    #[test]
+    fn test_seconds() {
+        let segment = WhisperVadSegment {
+            start: 150.0,
+            end: 400.0,
+        };
+        assert_eq!(segment.start_seconds(), 1.5);
+        assert_eq!(segment.end_seconds(), 4.0);
+        assert_eq!(segment.duration_seconds(), 2.5);
+    }
+}